@@ -0,0 +1,537 @@
+use serde::Serialize;
+use solana_sdk::{
+    hash::Hash,
+    nonce::state::{State as NonceState, Versions as NonceVersions},
+    pubkey::Pubkey,
+    system_instruction::SystemInstruction,
+    system_program,
+    transaction::VersionedTransaction,
+};
+use std::{
+    collections::{HashMap, hash_map::Entry},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::sync::RwLock;
+
+use crate::{
+    blockhash::BlockhashCache,
+    cluster::{Cluster, ClusterRegistry},
+    keystore::{ApprovalRequirement, Keystore, StoredKey},
+    policy::SigningPolicy,
+    rpc::rpc_error,
+    utils::{random_hex, unix_now},
+};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+const RETENTION_SECS: u64 = 3600;
+// A durable nonce never goes stale on its own, so nonce transactions waiting
+// for approval are dropped after a day. Policy changes get the same.
+const MAX_NONCE_AGE_SECS: u64 = 86_400;
+const POLICY_CHANGE_TTL_SECS: u64 = 86_400;
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum ApprovalStatus {
+    Pending,
+    Approved,
+    Rejected,
+    Expired,
+    Executed,
+}
+
+impl ApprovalStatus {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "pending" => Ok(Self::Pending),
+            "approved" => Ok(Self::Approved),
+            "rejected" => Ok(Self::Rejected),
+            "expired" => Ok(Self::Expired),
+            "executed" => Ok(Self::Executed),
+            _ => Err(format!(
+                "Invalid status: {} (expected pending, approved, rejected, expired or executed)",
+                value
+            )),
+        }
+    }
+
+    fn is_open(self) -> bool {
+        matches!(self, Self::Pending | Self::Approved)
+    }
+}
+
+// A parked transaction stays valid until its blockhash ages out, or, for
+// durable nonce transactions, until the nonce account is advanced. Policy
+// changes simply lapse after a fixed time.
+#[derive(Serialize, Clone, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ApprovalExpiry {
+    BlockHeight {
+        last_valid_block_height: u64,
+    },
+    Nonce {
+        account: String,
+        nonce: String,
+        expires_at: u64,
+    },
+    Deadline {
+        expires_at: u64,
+    },
+}
+
+// What the quorum is voting on.
+#[derive(Clone)]
+pub enum ApprovalAction {
+    Transaction {
+        cluster: String,
+        transaction: VersionedTransaction,
+        lamports: u64,
+        tokens: HashMap<String, u64>,
+    },
+    // Loosening the key's signing policy; `None` clears it.
+    Policy(Option<SigningPolicy>),
+}
+
+#[derive(Clone)]
+pub struct ApprovalRequest {
+    pub id: String,
+    pub key_id: String,
+    pub pubkey: String,
+    pub action: ApprovalAction,
+    pub quorum: u32,
+    pub requested_by: Option<String>,
+    pub approvals: Vec<String>,
+    pub rejected_by: Option<String>,
+    pub rejection: Option<String>,
+    pub status: ApprovalStatus,
+    pub expiry: ApprovalExpiry,
+    pub created_at: u64,
+    pub finished_at: Option<u64>,
+    pub signature: Option<String>,
+    executing: bool,
+}
+
+impl ApprovalRequest {
+    pub fn cluster(&self) -> Option<&str> {
+        match &self.action {
+            ApprovalAction::Transaction { cluster, .. } => Some(cluster),
+            ApprovalAction::Policy(_) => None,
+        }
+    }
+
+    fn finish(&mut self, status: ApprovalStatus) {
+        self.status = status;
+        self.finished_at = Some(unix_now());
+    }
+}
+
+// The nonce account of a durable nonce transaction: its first instruction
+// must be a system `AdvanceNonceAccount`.
+fn nonce_account(transaction: &VersionedTransaction) -> Option<Pubkey> {
+    let message = &transaction.message;
+    let keys = message.static_account_keys();
+    let first = message.instructions().first()?;
+
+    if keys.get(first.program_id_index as usize) != Some(&system_program::id()) {
+        return None;
+    }
+
+    match bincode::deserialize::<SystemInstruction>(&first.data) {
+        Ok(SystemInstruction::AdvanceNonceAccount) => first
+            .accounts
+            .first()
+            .and_then(|index| keys.get(*index as usize))
+            .copied(),
+        _ => None,
+    }
+}
+
+async fn stored_nonce(cluster: &Cluster, account: &Pubkey) -> Result<Option<Hash>, String> {
+    let response = cluster
+        .client
+        .get_account_with_commitment(account, cluster.commitment)
+        .await
+        .map_err(rpc_error)?;
+
+    let Some(account) = response.value else {
+        return Ok(None);
+    };
+
+    match bincode::deserialize::<NonceVersions>(&account.data) {
+        Ok(versions) => match versions.state() {
+            NonceState::Initialized(data) => Ok(Some(data.blockhash())),
+            NonceState::Uninitialized => Ok(None),
+        },
+        Err(_) => Ok(None),
+    }
+}
+
+pub async fn approval_expiry(
+    cluster: &Cluster,
+    blockhash: &BlockhashCache,
+    transaction: &VersionedTransaction,
+) -> Result<ApprovalExpiry, String> {
+    let Some(account) = nonce_account(transaction) else {
+        let last_valid_block_height = blockhash
+            .last_valid_block_height(cluster, transaction.message.recent_blockhash())
+            .await?;
+        return Ok(ApprovalExpiry::BlockHeight {
+            last_valid_block_height,
+        });
+    };
+
+    let nonce = *transaction.message.recent_blockhash();
+    if stored_nonce(cluster, &account).await? != Some(nonce) {
+        return Err(format!(
+            "Invalid transaction: nonce {} does not match nonce account {}",
+            nonce, account
+        ));
+    }
+
+    Ok(ApprovalExpiry::Nonce {
+        account: account.to_string(),
+        nonce: nonce.to_string(),
+        expires_at: unix_now() + MAX_NONCE_AGE_SECS,
+    })
+}
+
+// `block_height` is passed in so one lookup can serve every request on the
+// same cluster.
+pub async fn is_expired(
+    cluster: &Cluster,
+    expiry: &ApprovalExpiry,
+    block_height: Option<u64>,
+) -> Result<bool, String> {
+    match expiry {
+        ApprovalExpiry::BlockHeight {
+            last_valid_block_height,
+        } => {
+            let height = match block_height {
+                Some(height) => height,
+                None => cluster.client.get_block_height().await.map_err(rpc_error)?,
+            };
+            Ok(height > *last_valid_block_height)
+        }
+        ApprovalExpiry::Nonce {
+            account,
+            nonce,
+            expires_at,
+        } => {
+            if unix_now() >= *expires_at {
+                return Ok(true);
+            }
+            let account = account.parse::<Pubkey>().map_err(|e| e.to_string())?;
+            let current = stored_nonce(cluster, &account).await?;
+            Ok(current.map(|hash| hash.to_string()).as_deref() != Some(nonce.as_str()))
+        }
+        ApprovalExpiry::Deadline { expires_at } => Ok(unix_now() >= *expires_at),
+    }
+}
+
+#[derive(Default)]
+pub struct ApprovalQueue {
+    requests: RwLock<HashMap<String, ApprovalRequest>>,
+}
+
+impl ApprovalQueue {
+    // Submitting the same transaction for the same key again returns the
+    // request that is already open instead of starting a second vote.
+    pub async fn submit(
+        &self,
+        requirement: ApprovalRequirement,
+        cluster: &str,
+        transaction: VersionedTransaction,
+        requested_by: Option<String>,
+        expiry: ApprovalExpiry,
+    ) -> (ApprovalRequest, bool) {
+        let mut requests = self.requests.write().await;
+        let key_id = requirement.stored.id;
+
+        let message = transaction.message.serialize();
+        if let Some(existing) = requests.values().find(|r| {
+            r.status.is_open()
+                && r.key_id == key_id
+                && matches!(&r.action, ApprovalAction::Transaction { cluster: c, transaction: t, .. }
+                    if c == cluster && t.message.serialize() == message)
+        }) {
+            return (existing.clone(), false);
+        }
+
+        let request = ApprovalRequest {
+            id: random_hex(16),
+            key_id,
+            pubkey: requirement.stored.pubkey,
+            action: ApprovalAction::Transaction {
+                cluster: cluster.to_string(),
+                transaction,
+                lamports: requirement.spend.lamports,
+                tokens: requirement.spend.tokens,
+            },
+            quorum: requirement.quorum,
+            requested_by,
+            approvals: Vec::new(),
+            rejected_by: None,
+            rejection: None,
+            status: ApprovalStatus::Pending,
+            expiry,
+            created_at: unix_now(),
+            finished_at: None,
+            signature: None,
+            executing: false,
+        };
+
+        requests.insert(request.id.clone(), request.clone());
+        (request, true)
+    }
+
+    // Proposing the same policy for the same key again returns the request
+    // that is already open.
+    pub async fn submit_policy(
+        &self,
+        stored: &StoredKey,
+        quorum: u32,
+        policy: Option<SigningPolicy>,
+        requested_by: Option<String>,
+    ) -> (ApprovalRequest, bool) {
+        let mut requests = self.requests.write().await;
+
+        let proposed = serde_json::to_value(&policy).ok();
+        if let Some(existing) = requests.values().find(|r| {
+            r.status.is_open()
+                && r.key_id == stored.id
+                && matches!(&r.action, ApprovalAction::Policy(p)
+                    if serde_json::to_value(p).ok() == proposed)
+        }) {
+            return (existing.clone(), false);
+        }
+
+        let now = unix_now();
+        let request = ApprovalRequest {
+            id: random_hex(16),
+            key_id: stored.id.clone(),
+            pubkey: stored.pubkey.clone(),
+            action: ApprovalAction::Policy(policy),
+            quorum,
+            requested_by,
+            approvals: Vec::new(),
+            rejected_by: None,
+            rejection: None,
+            status: ApprovalStatus::Pending,
+            expiry: ApprovalExpiry::Deadline {
+                expires_at: now + POLICY_CHANGE_TTL_SECS,
+            },
+            created_at: now,
+            finished_at: None,
+            signature: None,
+            executing: false,
+        };
+
+        requests.insert(request.id.clone(), request.clone());
+        (request, true)
+    }
+
+    pub async fn get(&self, id: &str) -> Option<ApprovalRequest> {
+        self.requests.read().await.get(id).cloned()
+    }
+
+    pub async fn list(&self, status: Option<ApprovalStatus>) -> Vec<ApprovalRequest> {
+        let mut requests: Vec<_> = self
+            .requests
+            .read()
+            .await
+            .values()
+            .filter(|r| status.is_none_or(|s| r.status == s))
+            .cloned()
+            .collect();
+        requests.sort_by_key(|r| r.created_at);
+        requests
+    }
+
+    // Each principal counts once, and the principal that asked for the
+    // signature cannot approve it.
+    pub async fn approve(&self, id: &str, principal: &str) -> Result<ApprovalRequest, String> {
+        let mut requests = self.requests.write().await;
+        let request = requests
+            .get_mut(id)
+            .ok_or_else(|| "Approval not found".to_string())?;
+
+        if request.status != ApprovalStatus::Pending {
+            return Err(format!("Approval is {}", status_name(request.status)));
+        }
+        if request.requested_by.as_deref() == Some(principal) {
+            return Err("Requester cannot approve their own request".to_string());
+        }
+        if request.approvals.iter().any(|p| p == principal) {
+            return Err(format!("Already approved by {}", principal));
+        }
+
+        request.approvals.push(principal.to_string());
+        if request.approvals.len() >= request.quorum as usize {
+            request.status = ApprovalStatus::Approved;
+        }
+
+        Ok(request.clone())
+    }
+
+    // A single rejection closes the request, even after quorum was reached.
+    pub async fn reject(
+        &self,
+        id: &str,
+        principal: &str,
+        reason: Option<String>,
+    ) -> Result<ApprovalRequest, String> {
+        let mut requests = self.requests.write().await;
+        let request = requests
+            .get_mut(id)
+            .ok_or_else(|| "Approval not found".to_string())?;
+
+        if !request.status.is_open() || request.executing {
+            return Err(format!("Approval is {}", status_name(request.status)));
+        }
+
+        request.rejected_by = Some(principal.to_string());
+        request.rejection = reason;
+        request.finish(ApprovalStatus::Rejected);
+
+        Ok(request.clone())
+    }
+
+    // Claims an approved request so it is signed at most once.
+    pub async fn begin_execution(&self, id: &str) -> Result<ApprovalRequest, String> {
+        let mut requests = self.requests.write().await;
+        let request = requests
+            .get_mut(id)
+            .ok_or_else(|| "Approval not found".to_string())?;
+
+        match request.status {
+            ApprovalStatus::Approved if !request.executing => {
+                request.executing = true;
+                Ok(request.clone())
+            }
+            ApprovalStatus::Approved => Err("Approval is already being executed".to_string()),
+            ApprovalStatus::Pending => Err(format!(
+                "Approval quorum not met: {} of {} approvals",
+                request.approvals.len(),
+                request.quorum
+            )),
+            status => Err(format!("Approval is {}", status_name(status))),
+        }
+    }
+
+    pub async fn abort_execution(&self, id: &str) {
+        if let Some(request) = self.requests.write().await.get_mut(id) {
+            request.executing = false;
+        }
+    }
+
+    pub async fn complete_execution(
+        &self,
+        id: &str,
+        signature: Option<String>,
+    ) -> Option<ApprovalRequest> {
+        let mut requests = self.requests.write().await;
+        let request = requests.get_mut(id)?;
+        request.executing = false;
+        request.signature = signature;
+        request.finish(ApprovalStatus::Executed);
+        Some(request.clone())
+    }
+
+    pub async fn expire(&self, id: &str) -> Option<ApprovalRequest> {
+        let mut requests = self.requests.write().await;
+        let request = requests.get_mut(id)?;
+        if !request.status.is_open() {
+            return None;
+        }
+        request.executing = false;
+        request.finish(ApprovalStatus::Expired);
+        Some(request.clone())
+    }
+
+    // Marks open requests whose blockhash or nonce has gone stale and drops
+    // closed ones after the retention period. RPC failures leave a request
+    // open; it is checked again on the next pass.
+    async fn refresh(&self, clusters: &ClusterRegistry) -> Vec<ApprovalRequest> {
+        let now = unix_now();
+        self.requests.write().await.retain(|_, r| {
+            r.finished_at
+                .is_none_or(|finished| now.saturating_sub(finished) < RETENTION_SECS)
+        });
+
+        let open: Vec<_> = self
+            .list(None)
+            .await
+            .into_iter()
+            .filter(|r| r.status.is_open() && !r.executing)
+            .collect();
+
+        let mut heights: HashMap<String, Option<u64>> = HashMap::new();
+        let mut expired = Vec::new();
+
+        for request in open {
+            if let ApprovalExpiry::Deadline { expires_at } = request.expiry {
+                if now >= expires_at
+                    && let Some(request) = self.expire(&request.id).await
+                {
+                    expired.push(request);
+                }
+                continue;
+            }
+
+            let Ok(cluster) = clusters.resolve(request.cluster()) else {
+                continue;
+            };
+
+            let height = match heights.entry(cluster.name.clone()) {
+                Entry::Occupied(entry) => *entry.get(),
+                Entry::Vacant(entry) => *entry.insert(cluster.client.get_block_height().await.ok()),
+            };
+
+            if matches!(request.expiry, ApprovalExpiry::BlockHeight { .. }) && height.is_none() {
+                continue;
+            }
+
+            match is_expired(cluster, &request.expiry, height).await {
+                Ok(true) => {
+                    if let Some(request) = self.expire(&request.id).await {
+                        expired.push(request);
+                    }
+                }
+                Ok(false) => {}
+                Err(e) => println!("⚠️  Approval {} expiry check failed: {}", request.id, e),
+            }
+        }
+
+        expired
+    }
+
+    pub fn spawn_monitor(self: Arc<Self>, clusters: Arc<ClusterRegistry>, keystore: Arc<Keystore>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(POLL_INTERVAL);
+            loop {
+                interval.tick().await;
+
+                for request in self.refresh(&clusters).await {
+                    if let Some(stored) = keystore.resolve(&request.key_id).await {
+                        keystore.record_event(
+                            &stored,
+                            "approval_expired",
+                            None,
+                            Some(format!("approval {}", request.id)),
+                        );
+                    }
+                }
+            }
+        });
+    }
+}
+
+pub fn status_name(status: ApprovalStatus) -> &'static str {
+    match status {
+        ApprovalStatus::Pending => "pending",
+        ApprovalStatus::Approved => "approved",
+        ApprovalStatus::Rejected => "rejected",
+        ApprovalStatus::Expired => "expired",
+        ApprovalStatus::Executed => "executed",
+    }
+}
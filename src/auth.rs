@@ -0,0 +1,51 @@
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+pub const API_KEYS_ENV: &str = "API_KEYS";
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+// API keys identify principals for operations that need a named actor, such
+// as approvals. Configured as `API_KEYS=alice:token1,bob:token2`; only token
+// digests are kept in memory.
+#[derive(Default)]
+pub struct ApiKeys {
+    principals: HashMap<[u8; 32], String>,
+}
+
+fn digest(token: &str) -> [u8; 32] {
+    Sha256::digest(token.as_bytes()).into()
+}
+
+impl ApiKeys {
+    pub fn load() -> Result<Self, String> {
+        let Ok(config) = std::env::var(API_KEYS_ENV) else {
+            return Ok(Self::default());
+        };
+
+        let mut principals = HashMap::new();
+        for entry in config.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (name, token) = entry
+                .split_once(':')
+                .map(|(n, t)| (n.trim(), t.trim()))
+                .filter(|(n, t)| !n.is_empty() && !t.is_empty())
+                .ok_or_else(|| format!("Invalid {} entry: expected name:token", API_KEYS_ENV))?;
+
+            if principals.values().any(|existing| existing == name) {
+                return Err(format!("Duplicate principal in {}: {}", API_KEYS_ENV, name));
+            }
+            if principals.insert(digest(token), name.to_string()).is_some() {
+                return Err(format!("Duplicate token in {}", API_KEYS_ENV));
+            }
+        }
+
+        Ok(Self { principals })
+    }
+
+    pub fn principal_count(&self) -> usize {
+        self.principals.len()
+    }
+
+    pub fn principal(&self, token: &str) -> Option<String> {
+        self.principals.get(&digest(token.trim())).cloned()
+    }
+}
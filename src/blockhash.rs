@@ -0,0 +1,104 @@
+use solana_sdk::{clock::MAX_PROCESSING_AGE, hash::Hash};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::RwLock;
+
+use crate::{
+    cluster::{Cluster, ClusterRegistry},
+    rpc::rpc_error,
+};
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+// Well inside the ~60s a blockhash stays usable, so a cached value handed out
+// just before this limit still leaves the client time to sign and send.
+const MAX_AGE: Duration = Duration::from_secs(20);
+
+#[derive(Clone, Copy)]
+pub struct CachedBlockhash {
+    pub blockhash: Hash,
+    pub last_valid_block_height: u64,
+    pub fetched_at: Instant,
+}
+
+// Only clusters that have been asked for at least once (plus the default) are
+// refreshed in the background, so idle built-in clusters cost nothing.
+#[derive(Default)]
+pub struct BlockhashCache {
+    entries: RwLock<HashMap<String, CachedBlockhash>>,
+}
+
+impl BlockhashCache {
+    pub async fn latest(&self, cluster: &Cluster) -> Result<CachedBlockhash, String> {
+        if let Some(entry) = self.entries.read().await.get(&cluster.name)
+            && entry.fetched_at.elapsed() < MAX_AGE
+        {
+            return Ok(*entry);
+        }
+
+        self.refresh(cluster).await
+    }
+
+    // Transactions built against the cached blockhash get its exact expiry;
+    // for anything else it is estimated from the current block height.
+    pub async fn last_valid_block_height(
+        &self,
+        cluster: &Cluster,
+        blockhash: &Hash,
+    ) -> Result<u64, String> {
+        if let Ok(latest) = self.latest(cluster).await
+            && latest.blockhash == *blockhash
+        {
+            return Ok(latest.last_valid_block_height);
+        }
+
+        let height = cluster.client.get_block_height().await.map_err(rpc_error)?;
+        Ok(height + MAX_PROCESSING_AGE as u64)
+    }
+
+    async fn refresh(&self, cluster: &Cluster) -> Result<CachedBlockhash, String> {
+        let (blockhash, last_valid_block_height) = cluster
+            .client
+            .get_latest_blockhash_with_commitment(cluster.commitment)
+            .await
+            .map_err(rpc_error)?;
+
+        let entry = CachedBlockhash {
+            blockhash,
+            last_valid_block_height,
+            fetched_at: Instant::now(),
+        };
+
+        self.entries
+            .write()
+            .await
+            .insert(cluster.name.clone(), entry);
+
+        Ok(entry)
+    }
+
+    pub fn spawn_refresher(self: Arc<Self>, clusters: Arc<ClusterRegistry>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+            loop {
+                interval.tick().await;
+
+                let mut active: Vec<String> = self.entries.read().await.keys().cloned().collect();
+                if !active.iter().any(|name| name == clusters.default_name()) {
+                    active.push(clusters.default_name().to_string());
+                }
+
+                for name in active {
+                    let Ok(cluster) = clusters.resolve(Some(&name)) else {
+                        continue;
+                    };
+                    if let Err(e) = self.refresh(cluster).await {
+                        println!("⚠️  Blockhash refresh failed for {}: {}", name, e);
+                    }
+                }
+            }
+        });
+    }
+}
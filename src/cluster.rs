@@ -0,0 +1,260 @@
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::{collections::BTreeMap, fs, sync::Arc};
+
+use crate::rpc::rpc_error;
+
+pub const CLUSTERS_CONFIG_ENV: &str = "SOLANA_CLUSTERS_CONFIG";
+pub const RPC_URL_ENV: &str = "SOLANA_RPC_URL";
+pub const CLUSTER_ENV: &str = "SOLANA_CLUSTER";
+pub const CLUSTER_HEADER: &str = "x-solana-cluster";
+
+const DEFAULT_CLUSTER: &str = "devnet";
+const MAINNET_GENESIS_HASH: &str = "5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d";
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum ClusterKind {
+    Mainnet,
+    Devnet,
+    Testnet,
+    Localnet,
+    Custom,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ClusterConfig {
+    rpc_url: String,
+    #[serde(default)]
+    ws_url: Option<String>,
+    #[serde(default)]
+    kind: Option<ClusterKind>,
+    #[serde(default)]
+    commitment: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct ClustersFile {
+    #[serde(default)]
+    default: Option<String>,
+    #[serde(default)]
+    clusters: BTreeMap<String, ClusterConfig>,
+}
+
+pub struct Cluster {
+    pub name: String,
+    pub kind: ClusterKind,
+    pub commitment: CommitmentConfig,
+    pub client: Arc<RpcClient>,
+    pub ws_url: String,
+}
+
+impl Cluster {
+    fn new(name: &str, config: ClusterConfig) -> Result<Self, String> {
+        let kind = config.kind.unwrap_or_else(|| guess_kind(name, &config.rpc_url));
+
+        let commitment = match config.commitment.as_deref() {
+            Some("processed") => CommitmentConfig::processed(),
+            Some("confirmed") => CommitmentConfig::confirmed(),
+            Some("finalized") => CommitmentConfig::finalized(),
+            Some(other) => {
+                return Err(format!("Invalid commitment for cluster {}: {}", name, other));
+            }
+            None if kind == ClusterKind::Mainnet => CommitmentConfig::finalized(),
+            None => CommitmentConfig::confirmed(),
+        };
+
+        let ws_url = config
+            .ws_url
+            .unwrap_or_else(|| websocket_url(&config.rpc_url));
+
+        Ok(Self {
+            name: name.to_string(),
+            kind,
+            ws_url,
+            client: Arc::new(RpcClient::new_with_commitment(config.rpc_url, commitment)),
+            commitment,
+        })
+    }
+
+    // A custom URL can still point at mainnet, so the genesis hash is checked
+    // as well as the configured kind.
+    pub async fn is_mainnet(&self) -> Result<bool, String> {
+        if self.kind == ClusterKind::Mainnet {
+            return Ok(true);
+        }
+
+        let genesis = self.client.get_genesis_hash().await.map_err(rpc_error)?;
+        Ok(genesis.to_string() == MAINNET_GENESIS_HASH)
+    }
+}
+
+#[cfg(test)]
+impl Cluster {
+    pub fn local(rpc_url: &str) -> Self {
+        Self::new(
+            "localnet",
+            ClusterConfig {
+                rpc_url: rpc_url.to_string(),
+                ws_url: None,
+                kind: Some(ClusterKind::Localnet),
+                commitment: None,
+            },
+        )
+        .expect("valid cluster config")
+    }
+}
+
+// Same convention as the Solana CLI: swap the scheme and, when the RPC URL has
+// an explicit port, use the next one up for pubsub.
+fn websocket_url(rpc_url: &str) -> String {
+    let (scheme, rest) = match rpc_url.split_once("://") {
+        Some(("https", rest)) => ("wss", rest),
+        Some((_, rest)) => ("ws", rest),
+        None => ("ws", rpc_url),
+    };
+
+    let (authority, path) = match rest.find('/') {
+        Some(i) => rest.split_at(i),
+        None => (rest, ""),
+    };
+
+    let authority = match authority.rsplit_once(':') {
+        Some((host, port)) => match port.parse::<u16>() {
+            Ok(port) => format!("{}:{}", host, port.saturating_add(1)),
+            Err(_) => authority.to_string(),
+        },
+        None => authority.to_string(),
+    };
+
+    format!("{}://{}{}", scheme, authority, path)
+}
+
+fn guess_kind(name: &str, url: &str) -> ClusterKind {
+    let haystack = format!("{} {}", name, url);
+    if haystack.contains("mainnet") {
+        ClusterKind::Mainnet
+    } else if haystack.contains("testnet") {
+        ClusterKind::Testnet
+    } else if haystack.contains("devnet") {
+        ClusterKind::Devnet
+    } else if haystack.contains("localhost") || haystack.contains("127.0.0.1") {
+        ClusterKind::Localnet
+    } else {
+        ClusterKind::Custom
+    }
+}
+
+fn builtin(rpc_url: &str, kind: ClusterKind) -> ClusterConfig {
+    ClusterConfig {
+        rpc_url: rpc_url.to_string(),
+        ws_url: None,
+        kind: Some(kind),
+        commitment: None,
+    }
+}
+
+pub struct ClusterRegistry {
+    clusters: BTreeMap<String, Cluster>,
+    default: String,
+}
+
+impl ClusterRegistry {
+    // Built-in clusters are overridden by the JSON file named in
+    // `SOLANA_CLUSTERS_CONFIG`, and `SOLANA_RPC_URL` (with `SOLANA_CLUSTER` as
+    // its name) still works for single-cluster deployments.
+    pub fn load() -> Result<Self, String> {
+        let mut configs: BTreeMap<String, ClusterConfig> = BTreeMap::new();
+        configs.insert(
+            "mainnet".to_string(),
+            builtin("https://api.mainnet-beta.solana.com", ClusterKind::Mainnet),
+        );
+        configs.insert(
+            "devnet".to_string(),
+            builtin("https://api.devnet.solana.com", ClusterKind::Devnet),
+        );
+        configs.insert(
+            "testnet".to_string(),
+            builtin("https://api.testnet.solana.com", ClusterKind::Testnet),
+        );
+        configs.insert(
+            "localnet".to_string(),
+            builtin("http://127.0.0.1:8899", ClusterKind::Localnet),
+        );
+
+        let file = match std::env::var(CLUSTERS_CONFIG_ENV) {
+            Ok(path) => {
+                let contents = fs::read_to_string(&path)
+                    .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+                serde_json::from_str::<ClustersFile>(&contents)
+                    .map_err(|e| format!("Invalid cluster config {}: {}", path, e))?
+            }
+            Err(_) => ClustersFile::default(),
+        };
+
+        for (name, config) in file.clusters {
+            configs.insert(normalize_name(&name), config);
+        }
+
+        let env_cluster = std::env::var(CLUSTER_ENV).ok().map(|c| normalize_name(&c));
+        let mut default = file.default.map(|d| normalize_name(&d));
+
+        if let Ok(url) = std::env::var(RPC_URL_ENV) {
+            let name = env_cluster.clone().unwrap_or_else(|| "custom".to_string());
+            // Only the URL is overridden; commitment, websocket URL and kind
+            // configured for that cluster still apply.
+            configs
+                .entry(name.clone())
+                .and_modify(|existing| existing.rpc_url = url.clone())
+                .or_insert_with(|| ClusterConfig {
+                    rpc_url: url,
+                    ws_url: None,
+                    kind: None,
+                    commitment: None,
+                });
+            default = Some(name);
+        }
+
+        let default = default
+            .or(env_cluster)
+            .unwrap_or_else(|| DEFAULT_CLUSTER.to_string());
+
+        let clusters = configs
+            .into_iter()
+            .map(|(name, config)| Cluster::new(&name, config).map(|c| (name, c)))
+            .collect::<Result<BTreeMap<_, _>, _>>()?;
+
+        if !clusters.contains_key(&default) {
+            return Err(format!("Default cluster {} is not configured", default));
+        }
+
+        Ok(Self { clusters, default })
+    }
+
+    pub fn resolve(&self, name: Option<&str>) -> Result<&Cluster, String> {
+        match name.map(str::trim).filter(|n| !n.is_empty()) {
+            Some(name) => self
+                .clusters
+                .get(&normalize_name(name))
+                .ok_or_else(|| format!("Unknown cluster: {}", name)),
+            None => Ok(&self.clusters[&self.default]),
+        }
+    }
+
+    pub fn default_name(&self) -> &str {
+        &self.default
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Cluster> {
+        self.clusters.values()
+    }
+}
+
+fn normalize_name(name: &str) -> String {
+    match name.trim().to_lowercase().as_str() {
+        "mainnet-beta" => "mainnet".to_string(),
+        other => other.to_string(),
+    }
+}
@@ -0,0 +1,542 @@
+use serde_json::{Value, json};
+use solana_sdk::{
+    message::VersionedMessage,
+    pubkey,
+    pubkey::Pubkey,
+    stake::{self, instruction::StakeInstruction, state::StakeAuthorize},
+    system_instruction::SystemInstruction,
+    system_program,
+};
+use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta, UiLoadedAddresses, UiTransactionTokenBalance,
+};
+use spl_token::instruction::{AuthorityType, TokenInstruction};
+use std::collections::BTreeMap;
+
+use crate::types::response::{AccountInfo, DecodedInstruction, TokenBalanceChangeResponse};
+
+pub const MEMO_PROGRAM_ID: Pubkey = pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+pub const MEMO_V1_PROGRAM_ID: Pubkey = pubkey!("Memo1UhkJRfHyvLMcVucJwxXeuD728EqVDDwQDxFMNo");
+
+// Account keys referenced through an address lookup table can only be
+// resolved with the table's contents (e.g. `loadedAddresses` from transaction
+// meta); without them they are reported as `<table>:<index>`.
+pub fn message_account_keys(
+    message: &VersionedMessage,
+    loaded: Option<(&[String], &[String])>,
+) -> Vec<String> {
+    let mut keys: Vec<String> = message
+        .static_account_keys()
+        .iter()
+        .map(|k| k.to_string())
+        .collect();
+
+    if let Some((writable, readonly)) = loaded {
+        keys.extend(writable.iter().cloned());
+        keys.extend(readonly.iter().cloned());
+    } else if let Some(lookups) = message.address_table_lookups() {
+        for lookup in lookups {
+            for index in &lookup.writable_indexes {
+                keys.push(format!("{}:{}", lookup.account_key, index));
+            }
+        }
+        for lookup in lookups {
+            for index in &lookup.readonly_indexes {
+                keys.push(format!("{}:{}", lookup.account_key, index));
+            }
+        }
+    }
+
+    keys
+}
+
+pub fn decode_message_instructions(
+    message: &VersionedMessage,
+    loaded: Option<(&[String], &[String])>,
+) -> Vec<DecodedInstruction> {
+    let keys = message_account_keys(message, loaded);
+
+    message
+        .instructions()
+        .iter()
+        .map(|ix| {
+            let program_id = keys
+                .get(ix.program_id_index as usize)
+                .cloned()
+                .unwrap_or_default();
+
+            let accounts: Vec<AccountInfo> = ix
+                .accounts
+                .iter()
+                .map(|&i| AccountInfo {
+                    pubkey: keys.get(i as usize).cloned().unwrap_or_default(),
+                    is_signer: message.is_signer(i as usize),
+                    is_writable: message.is_maybe_writable(i as usize),
+                })
+                .collect();
+
+            let account_keys: Vec<String> = accounts.iter().map(|a| a.pubkey.clone()).collect();
+            decode_instruction(&program_id, accounts, &account_keys, &ix.data)
+        })
+        .collect()
+}
+
+fn loaded_addresses(tx: &EncodedConfirmedTransactionWithStatusMeta) -> Option<UiLoadedAddresses> {
+    tx.transaction
+        .meta
+        .as_ref()
+        .and_then(|meta| Option::from(meta.loaded_addresses.clone()))
+}
+
+pub fn confirmed_transaction_account_keys(
+    tx: &EncodedConfirmedTransactionWithStatusMeta,
+) -> Vec<String> {
+    let Some(transaction) = tx.transaction.transaction.decode() else {
+        return Vec::new();
+    };
+    let loaded = loaded_addresses(tx);
+
+    message_account_keys(
+        &transaction.message,
+        loaded
+            .as_ref()
+            .map(|l| (l.writable.as_slice(), l.readonly.as_slice())),
+    )
+}
+
+pub fn decode_confirmed_transaction(
+    tx: &EncodedConfirmedTransactionWithStatusMeta,
+) -> Vec<DecodedInstruction> {
+    let Some(transaction) = tx.transaction.transaction.decode() else {
+        return Vec::new();
+    };
+    let loaded = loaded_addresses(tx);
+
+    decode_message_instructions(
+        &transaction.message,
+        loaded
+            .as_ref()
+            .map(|l| (l.writable.as_slice(), l.readonly.as_slice())),
+    )
+}
+
+pub fn token_balance_changes(
+    account_keys: &[String],
+    pre: Vec<UiTransactionTokenBalance>,
+    post: Vec<UiTransactionTokenBalance>,
+) -> Vec<TokenBalanceChangeResponse> {
+    let mut by_index: BTreeMap<u8, TokenBalanceChangeResponse> = BTreeMap::new();
+
+    for (balance, is_post) in pre
+        .into_iter()
+        .map(|b| (b, false))
+        .chain(post.into_iter().map(|b| (b, true)))
+    {
+        let entry = by_index
+            .entry(balance.account_index)
+            .or_insert_with(|| TokenBalanceChangeResponse {
+                account: account_keys
+                    .get(balance.account_index as usize)
+                    .cloned()
+                    .unwrap_or_default(),
+                mint: balance.mint.clone(),
+                owner: Option::from(balance.owner.clone()),
+                decimals: balance.ui_token_amount.decimals,
+                pre_amount: "0".to_string(),
+                post_amount: "0".to_string(),
+            });
+
+        if is_post {
+            entry.post_amount = balance.ui_token_amount.amount;
+        } else {
+            entry.pre_amount = balance.ui_token_amount.amount;
+        }
+    }
+
+    by_index.into_values().collect()
+}
+
+pub fn decode_instruction(
+    program_id: &str,
+    accounts: Vec<AccountInfo>,
+    account_keys: &[String],
+    data: &[u8],
+) -> DecodedInstruction {
+    use base64::{Engine as _, engine::general_purpose};
+
+    let parsed = program_id
+        .parse::<Pubkey>()
+        .ok()
+        .and_then(|id| parse_known_instruction(&id, account_keys, data));
+
+    let (program, instruction_type, info) = match parsed {
+        Some((program, ty, info)) => (Some(program.to_string()), Some(ty.to_string()), Some(info)),
+        None => (None, None, None),
+    };
+
+    DecodedInstruction {
+        program_id: program_id.to_string(),
+        program,
+        instruction_type,
+        accounts,
+        instruction_data: general_purpose::STANDARD.encode(data),
+        info,
+    }
+}
+
+fn parse_known_instruction(
+    program_id: &Pubkey,
+    accounts: &[String],
+    data: &[u8],
+) -> Option<(&'static str, &'static str, Value)> {
+    if *program_id == system_program::id() {
+        parse_system(accounts, data).map(|(t, v)| ("system", t, v))
+    } else if *program_id == spl_token::id() {
+        parse_token(accounts, data).map(|(t, v)| ("spl-token", t, v))
+    } else if *program_id == spl_associated_token_account::id() {
+        parse_associated_token(accounts, data).map(|(t, v)| ("spl-associated-token-account", t, v))
+    } else if *program_id == MEMO_PROGRAM_ID || *program_id == MEMO_V1_PROGRAM_ID {
+        std::str::from_utf8(data)
+            .ok()
+            .map(|memo| ("spl-memo", "memo", json!({ "memo": memo })))
+    } else if *program_id == solana_sdk::compute_budget::id() {
+        parse_compute_budget(data).map(|(t, v)| ("compute-budget", t, v))
+    } else if *program_id == stake::program::id() {
+        parse_stake(accounts, data).map(|(t, v)| ("stake", t, v))
+    } else {
+        None
+    }
+}
+
+fn account(accounts: &[String], index: usize) -> Value {
+    accounts
+        .get(index)
+        .map(|a| Value::String(a.clone()))
+        .unwrap_or(Value::Null)
+}
+
+fn parse_system(accounts: &[String], data: &[u8]) -> Option<(&'static str, Value)> {
+    let a = |i| account(accounts, i);
+    let ix: SystemInstruction = bincode::deserialize(data).ok()?;
+
+    Some(match ix {
+        SystemInstruction::CreateAccount {
+            lamports,
+            space,
+            owner,
+        } => (
+            "createAccount",
+            json!({ "from": a(0), "newAccount": a(1), "lamports": lamports, "space": space, "owner": owner.to_string() }),
+        ),
+        SystemInstruction::Assign { owner } => (
+            "assign",
+            json!({ "account": a(0), "owner": owner.to_string() }),
+        ),
+        SystemInstruction::Transfer { lamports } => (
+            "transfer",
+            json!({ "from": a(0), "to": a(1), "lamports": lamports }),
+        ),
+        SystemInstruction::CreateAccountWithSeed {
+            base,
+            seed,
+            lamports,
+            space,
+            owner,
+        } => (
+            "createAccountWithSeed",
+            json!({ "from": a(0), "newAccount": a(1), "base": base.to_string(), "seed": seed, "lamports": lamports, "space": space, "owner": owner.to_string() }),
+        ),
+        SystemInstruction::AdvanceNonceAccount => (
+            "advanceNonce",
+            json!({ "nonceAccount": a(0), "nonceAuthority": a(2) }),
+        ),
+        SystemInstruction::WithdrawNonceAccount(lamports) => (
+            "withdrawFromNonce",
+            json!({ "nonceAccount": a(0), "to": a(1), "nonceAuthority": a(4), "lamports": lamports }),
+        ),
+        SystemInstruction::InitializeNonceAccount(authority) => (
+            "initializeNonce",
+            json!({ "nonceAccount": a(0), "nonceAuthority": authority.to_string() }),
+        ),
+        SystemInstruction::AuthorizeNonceAccount(authority) => (
+            "authorizeNonce",
+            json!({ "nonceAccount": a(0), "nonceAuthority": a(1), "newAuthority": authority.to_string() }),
+        ),
+        SystemInstruction::Allocate { space } => {
+            ("allocate", json!({ "account": a(0), "space": space }))
+        }
+        SystemInstruction::AllocateWithSeed {
+            base,
+            seed,
+            space,
+            owner,
+        } => (
+            "allocateWithSeed",
+            json!({ "account": a(0), "base": base.to_string(), "seed": seed, "space": space, "owner": owner.to_string() }),
+        ),
+        SystemInstruction::AssignWithSeed { base, seed, owner } => (
+            "assignWithSeed",
+            json!({ "account": a(0), "base": base.to_string(), "seed": seed, "owner": owner.to_string() }),
+        ),
+        SystemInstruction::TransferWithSeed {
+            lamports,
+            from_seed,
+            from_owner,
+        } => (
+            "transferWithSeed",
+            json!({ "from": a(0), "fromBase": a(1), "to": a(2), "lamports": lamports, "fromSeed": from_seed, "fromOwner": from_owner.to_string() }),
+        ),
+        SystemInstruction::UpgradeNonceAccount => ("upgradeNonce", json!({ "nonceAccount": a(0) })),
+    })
+}
+
+fn authority_type_name(authority_type: &AuthorityType) -> &'static str {
+    match authority_type {
+        AuthorityType::MintTokens => "mintTokens",
+        AuthorityType::FreezeAccount => "freezeAccount",
+        AuthorityType::AccountOwner => "accountOwner",
+        AuthorityType::CloseAccount => "closeAccount",
+    }
+}
+
+fn parse_token(accounts: &[String], data: &[u8]) -> Option<(&'static str, Value)> {
+    let a = |i| account(accounts, i);
+    let ix = TokenInstruction::unpack(data).ok()?;
+    let optional_key = |key: Option<Pubkey>| {
+        key.map(|k| Value::String(k.to_string()))
+            .unwrap_or(Value::Null)
+    };
+
+    Some(match ix {
+        TokenInstruction::InitializeMint {
+            decimals,
+            mint_authority,
+            freeze_authority,
+        } => (
+            "initializeMint",
+            json!({ "mint": a(0), "mintAuthority": mint_authority.to_string(), "freezeAuthority": optional_key(freeze_authority.into()), "decimals": decimals }),
+        ),
+        TokenInstruction::InitializeMint2 {
+            decimals,
+            mint_authority,
+            freeze_authority,
+        } => (
+            "initializeMint2",
+            json!({ "mint": a(0), "mintAuthority": mint_authority.to_string(), "freezeAuthority": optional_key(freeze_authority.into()), "decimals": decimals }),
+        ),
+        TokenInstruction::InitializeAccount => (
+            "initializeAccount",
+            json!({ "account": a(0), "mint": a(1), "owner": a(2) }),
+        ),
+        TokenInstruction::InitializeAccount2 { owner } => (
+            "initializeAccount2",
+            json!({ "account": a(0), "mint": a(1), "owner": owner.to_string() }),
+        ),
+        TokenInstruction::InitializeAccount3 { owner } => (
+            "initializeAccount3",
+            json!({ "account": a(0), "mint": a(1), "owner": owner.to_string() }),
+        ),
+        TokenInstruction::InitializeMultisig { m } => (
+            "initializeMultisig",
+            json!({ "multisig": a(0), "m": m, "signers": accounts.get(2..).unwrap_or_default() }),
+        ),
+        TokenInstruction::InitializeMultisig2 { m } => (
+            "initializeMultisig2",
+            json!({ "multisig": a(0), "m": m, "signers": accounts.get(1..).unwrap_or_default() }),
+        ),
+        TokenInstruction::Transfer { amount } => (
+            "transfer",
+            json!({ "source": a(0), "destination": a(1), "owner": a(2), "amount": amount }),
+        ),
+        TokenInstruction::Approve { amount } => (
+            "approve",
+            json!({ "source": a(0), "delegate": a(1), "owner": a(2), "amount": amount }),
+        ),
+        TokenInstruction::Revoke => ("revoke", json!({ "source": a(0), "owner": a(1) })),
+        TokenInstruction::SetAuthority {
+            authority_type,
+            new_authority,
+        } => (
+            "setAuthority",
+            json!({ "account": a(0), "authority": a(1), "authorityType": authority_type_name(&authority_type), "newAuthority": optional_key(new_authority.into()) }),
+        ),
+        TokenInstruction::MintTo { amount } => (
+            "mintTo",
+            json!({ "mint": a(0), "destination": a(1), "authority": a(2), "amount": amount }),
+        ),
+        TokenInstruction::Burn { amount } => (
+            "burn",
+            json!({ "account": a(0), "mint": a(1), "authority": a(2), "amount": amount }),
+        ),
+        TokenInstruction::CloseAccount => (
+            "closeAccount",
+            json!({ "account": a(0), "destination": a(1), "owner": a(2) }),
+        ),
+        TokenInstruction::FreezeAccount => (
+            "freezeAccount",
+            json!({ "account": a(0), "mint": a(1), "authority": a(2) }),
+        ),
+        TokenInstruction::ThawAccount => (
+            "thawAccount",
+            json!({ "account": a(0), "mint": a(1), "authority": a(2) }),
+        ),
+        TokenInstruction::TransferChecked { amount, decimals } => (
+            "transferChecked",
+            json!({ "source": a(0), "mint": a(1), "destination": a(2), "owner": a(3), "amount": amount, "decimals": decimals }),
+        ),
+        TokenInstruction::ApproveChecked { amount, decimals } => (
+            "approveChecked",
+            json!({ "source": a(0), "mint": a(1), "delegate": a(2), "owner": a(3), "amount": amount, "decimals": decimals }),
+        ),
+        TokenInstruction::MintToChecked { amount, decimals } => (
+            "mintToChecked",
+            json!({ "mint": a(0), "destination": a(1), "authority": a(2), "amount": amount, "decimals": decimals }),
+        ),
+        TokenInstruction::BurnChecked { amount, decimals } => (
+            "burnChecked",
+            json!({ "account": a(0), "mint": a(1), "authority": a(2), "amount": amount, "decimals": decimals }),
+        ),
+        TokenInstruction::SyncNative => ("syncNative", json!({ "account": a(0) })),
+        TokenInstruction::GetAccountDataSize => ("getAccountDataSize", json!({ "mint": a(0) })),
+        TokenInstruction::InitializeImmutableOwner => {
+            ("initializeImmutableOwner", json!({ "account": a(0) }))
+        }
+        TokenInstruction::AmountToUiAmount { amount } => (
+            "amountToUiAmount",
+            json!({ "mint": a(0), "amount": amount }),
+        ),
+        TokenInstruction::UiAmountToAmount { ui_amount } => (
+            "uiAmountToAmount",
+            json!({ "mint": a(0), "uiAmount": ui_amount }),
+        ),
+    })
+}
+
+fn parse_associated_token(accounts: &[String], data: &[u8]) -> Option<(&'static str, Value)> {
+    let a = |i| account(accounts, i);
+    let info = json!({ "payer": a(0), "account": a(1), "wallet": a(2), "mint": a(3) });
+
+    match data.first() {
+        None | Some(0) => Some(("create", info)),
+        Some(1) => Some(("createIdempotent", info)),
+        Some(2) => Some((
+            "recoverNested",
+            json!({ "nestedSource": a(0), "nestedMint": a(1), "destination": a(2), "nestedOwner": a(3), "ownerMint": a(4), "wallet": a(5) }),
+        )),
+        _ => None,
+    }
+}
+
+fn parse_compute_budget(data: &[u8]) -> Option<(&'static str, Value)> {
+    let (tag, rest) = data.split_first()?;
+    let u32_at = |offset: usize| -> Option<u32> {
+        rest.get(offset..offset + 4)
+            .map(|b| u32::from_le_bytes(b.try_into().expect("4 bytes")))
+    };
+
+    match tag {
+        0 => Some((
+            "requestUnits",
+            json!({ "units": u32_at(0)?, "additionalFee": u32_at(4)? }),
+        )),
+        1 => Some(("requestHeapFrame", json!({ "bytes": u32_at(0)? }))),
+        2 => Some(("setComputeUnitLimit", json!({ "units": u32_at(0)? }))),
+        3 => {
+            let price = rest
+                .get(0..8)
+                .map(|b| u64::from_le_bytes(b.try_into().expect("8 bytes")))?;
+            Some(("setComputeUnitPrice", json!({ "microLamports": price })))
+        }
+        4 => Some((
+            "setLoadedAccountsDataSizeLimit",
+            json!({ "bytes": u32_at(0)? }),
+        )),
+        _ => None,
+    }
+}
+
+fn stake_authorize_name(authorize: &StakeAuthorize) -> &'static str {
+    match authorize {
+        StakeAuthorize::Staker => "staker",
+        StakeAuthorize::Withdrawer => "withdrawer",
+    }
+}
+
+fn parse_stake(accounts: &[String], data: &[u8]) -> Option<(&'static str, Value)> {
+    let a = |i| account(accounts, i);
+    let ix: StakeInstruction = bincode::deserialize(data).ok()?;
+
+    Some(match ix {
+        StakeInstruction::Initialize(authorized, lockup) => (
+            "initialize",
+            json!({
+                "stakeAccount": a(0),
+                "staker": authorized.staker.to_string(),
+                "withdrawer": authorized.withdrawer.to_string(),
+                "lockup": {
+                    "unixTimestamp": lockup.unix_timestamp,
+                    "epoch": lockup.epoch,
+                    "custodian": lockup.custodian.to_string(),
+                },
+            }),
+        ),
+        StakeInstruction::Authorize(new_authority, authorize) => (
+            "authorize",
+            json!({ "stakeAccount": a(0), "authority": a(2), "newAuthority": new_authority.to_string(), "authorityType": stake_authorize_name(&authorize) }),
+        ),
+        StakeInstruction::DelegateStake => (
+            "delegate",
+            json!({ "stakeAccount": a(0), "voteAccount": a(1), "stakeAuthority": a(5) }),
+        ),
+        StakeInstruction::Split(lamports) => (
+            "split",
+            json!({ "stakeAccount": a(0), "newSplitAccount": a(1), "stakeAuthority": a(2), "lamports": lamports }),
+        ),
+        StakeInstruction::Withdraw(lamports) => (
+            "withdraw",
+            json!({ "stakeAccount": a(0), "destination": a(1), "withdrawAuthority": a(4), "lamports": lamports }),
+        ),
+        StakeInstruction::Deactivate => (
+            "deactivate",
+            json!({ "stakeAccount": a(0), "stakeAuthority": a(2) }),
+        ),
+        StakeInstruction::SetLockup(args) => (
+            "setLockup",
+            json!({ "stakeAccount": a(0), "custodian": a(1), "unixTimestamp": args.unix_timestamp, "epoch": args.epoch, "newCustodian": args.custodian.map(|c| c.to_string()) }),
+        ),
+        StakeInstruction::Merge => (
+            "merge",
+            json!({ "destination": a(0), "source": a(1), "stakeAuthority": a(4) }),
+        ),
+        StakeInstruction::AuthorizeWithSeed(args) => (
+            "authorizeWithSeed",
+            json!({ "stakeAccount": a(0), "authorityBase": a(1), "newAuthority": args.new_authorized_pubkey.to_string(), "authorityType": stake_authorize_name(&args.stake_authorize), "authoritySeed": args.authority_seed, "authorityOwner": args.authority_owner.to_string() }),
+        ),
+        StakeInstruction::InitializeChecked => (
+            "initializeChecked",
+            json!({ "stakeAccount": a(0), "staker": a(2), "withdrawer": a(3) }),
+        ),
+        StakeInstruction::AuthorizeChecked(authorize) => (
+            "authorizeChecked",
+            json!({ "stakeAccount": a(0), "authority": a(2), "newAuthority": a(3), "authorityType": stake_authorize_name(&authorize) }),
+        ),
+        StakeInstruction::AuthorizeCheckedWithSeed(args) => (
+            "authorizeCheckedWithSeed",
+            json!({ "stakeAccount": a(0), "authorityBase": a(1), "newAuthority": a(3), "authorityType": stake_authorize_name(&args.stake_authorize), "authoritySeed": args.authority_seed, "authorityOwner": args.authority_owner.to_string() }),
+        ),
+        StakeInstruction::SetLockupChecked(args) => (
+            "setLockupChecked",
+            json!({ "stakeAccount": a(0), "custodian": a(1), "unixTimestamp": args.unix_timestamp, "epoch": args.epoch, "newCustodian": a(2) }),
+        ),
+        StakeInstruction::GetMinimumDelegation => ("getMinimumDelegation", json!({})),
+        StakeInstruction::DeactivateDelinquent => (
+            "deactivateDelinquent",
+            json!({ "stakeAccount": a(0), "voteAccount": a(1), "referenceVoteAccount": a(2) }),
+        ),
+        #[allow(deprecated)]
+        StakeInstruction::Redelegate => (
+            "redelegate",
+            json!({ "stakeAccount": a(0), "newStakeAccount": a(1), "voteAccount": a(2), "stakeAuthority": a(4) }),
+        ),
+    })
+}
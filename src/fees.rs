@@ -0,0 +1,195 @@
+use serde_json::Value;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    message::VersionedMessage, nonce::state::State as NonceState, program_pack::Pack,
+    pubkey::Pubkey,
+};
+use spl_token_2022::extension::ExtensionType;
+use std::collections::HashMap;
+
+use crate::{
+    decoder::decode_message_instructions,
+    rpc::rpc_error,
+    types::response::{DecodedInstruction, RentDepositResponse},
+};
+
+const ATA_TOKEN_PROGRAM_INDEX: usize = 5;
+
+struct Deposit {
+    account: String,
+    kind: &'static str,
+    space: u64,
+    funder: String,
+    // Known up front for `createAccount`; otherwise the rent-exempt minimum.
+    lamports: Option<u64>,
+}
+
+pub struct FeeBreakdown {
+    pub rent_deposits: Vec<RentDepositResponse>,
+    pub transfer_total: u64,
+}
+
+fn info_str(info: &Value, field: &str) -> Option<String> {
+    info.get(field).and_then(Value::as_str).map(str::to_string)
+}
+
+fn info_u64(info: &Value, field: &str) -> u64 {
+    info.get(field).and_then(Value::as_u64).unwrap_or(0)
+}
+
+fn token_account_space(instruction: &DecodedInstruction) -> u64 {
+    let is_token_2022 = instruction
+        .accounts
+        .get(ATA_TOKEN_PROGRAM_INDEX)
+        .is_some_and(|acc| acc.pubkey == spl_token_2022::id().to_string());
+
+    if is_token_2022 {
+        ExtensionType::try_calculate_account_len::<spl_token_2022::state::Account>(&[
+            ExtensionType::ImmutableOwner,
+        ])
+        .unwrap_or(spl_token::state::Account::LEN) as u64
+    } else {
+        spl_token::state::Account::LEN as u64
+    }
+}
+
+fn deposit_for(instruction: &DecodedInstruction, fee_payer: &str) -> Option<Deposit> {
+    let info = instruction.info.as_ref()?;
+    let program = instruction.program.as_deref()?;
+    let instruction_type = instruction.instruction_type.as_deref()?;
+
+    let (account, kind, space, funder, lamports) = match (program, instruction_type) {
+        ("system", "createAccount" | "createAccountWithSeed") => (
+            info_str(info, "newAccount")?,
+            "account",
+            info_u64(info, "space"),
+            info_str(info, "from")?,
+            Some(info_u64(info, "lamports")),
+        ),
+        ("system", "initializeNonce") => (
+            info_str(info, "nonceAccount")?,
+            "nonce",
+            NonceState::size() as u64,
+            fee_payer.to_string(),
+            None,
+        ),
+        ("spl-token", "initializeMint" | "initializeMint2") => (
+            info_str(info, "mint")?,
+            "mint",
+            spl_token::state::Mint::LEN as u64,
+            fee_payer.to_string(),
+            None,
+        ),
+        ("spl-token", "initializeAccount" | "initializeAccount2" | "initializeAccount3") => (
+            info_str(info, "account")?,
+            "tokenAccount",
+            spl_token::state::Account::LEN as u64,
+            fee_payer.to_string(),
+            None,
+        ),
+        ("spl-associated-token-account", "create" | "createIdempotent") => (
+            info_str(info, "account")?,
+            "associatedTokenAccount",
+            token_account_space(instruction),
+            info_str(info, "payer")?,
+            None,
+        ),
+        _ => return None,
+    };
+
+    Some(Deposit {
+        account,
+        kind,
+        space,
+        funder,
+        lamports,
+    })
+}
+
+// Walks the decoded instructions for accounts the transaction brings into
+// existence. Accounts that are only initialized (not created in the same
+// message) are charged to the fee payer unless they already exist on chain.
+pub async fn estimate_fee_breakdown(
+    client: &RpcClient,
+    message: &VersionedMessage,
+) -> Result<FeeBreakdown, String> {
+    let fee_payer = message
+        .static_account_keys()
+        .first()
+        .map(|key| key.to_string())
+        .unwrap_or_default();
+
+    let mut deposits: Vec<Deposit> = Vec::new();
+    let mut transfer_total: u64 = 0;
+
+    for instruction in decode_message_instructions(message, None) {
+        if instruction.program.as_deref() == Some("system")
+            && instruction.instruction_type.as_deref() == Some("transfer")
+            && let Some(info) = instruction.info.as_ref()
+            && info_str(info, "from").as_deref() == Some(fee_payer.as_str())
+        {
+            transfer_total = transfer_total.saturating_add(info_u64(info, "lamports"));
+        }
+
+        if let Some(deposit) = deposit_for(&instruction, &fee_payer)
+            && !deposits.iter().any(|d| d.account == deposit.account)
+        {
+            deposits.push(deposit);
+        }
+    }
+
+    let unfunded: Vec<Pubkey> = deposits
+        .iter()
+        .filter(|d| d.lamports.is_none())
+        .filter_map(|d| d.account.parse().ok())
+        .collect();
+
+    if !unfunded.is_empty() {
+        let existing = client
+            .get_multiple_accounts(&unfunded)
+            .await
+            .map_err(rpc_error)?;
+
+        let existing: Vec<String> = unfunded
+            .iter()
+            .zip(existing)
+            .filter(|(_, account)| account.is_some())
+            .map(|(key, _)| key.to_string())
+            .collect();
+
+        deposits.retain(|d| d.lamports.is_some() || !existing.contains(&d.account));
+    }
+
+    let mut rent_by_space: HashMap<u64, u64> = HashMap::new();
+    let mut rent_deposits = Vec::with_capacity(deposits.len());
+
+    for deposit in deposits {
+        let lamports = match deposit.lamports {
+            Some(lamports) => lamports,
+            None => match rent_by_space.get(&deposit.space) {
+                Some(rent) => *rent,
+                None => {
+                    let rent = client
+                        .get_minimum_balance_for_rent_exemption(deposit.space as usize)
+                        .await
+                        .map_err(rpc_error)?;
+                    rent_by_space.insert(deposit.space, rent);
+                    rent
+                }
+            },
+        };
+
+        rent_deposits.push(RentDepositResponse {
+            account: deposit.account,
+            kind: deposit.kind.to_string(),
+            space: deposit.space,
+            lamports,
+            funder: deposit.funder,
+        });
+    }
+
+    Ok(FeeBreakdown {
+        rent_deposits,
+        transfer_total,
+    })
+}
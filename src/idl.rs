@@ -0,0 +1,699 @@
+use heck::ToSnakeCase;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use solana_sdk::{
+    hash::hash,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+use std::{collections::HashMap, fs, path::PathBuf, str::FromStr, sync::Arc};
+
+pub const IDL_DIR_ENV: &str = "ANCHOR_IDL_DIR";
+const DEFAULT_IDL_DIR: &str = "idls";
+
+// Both the legacy (< 0.30) and the current Anchor IDL layouts are accepted;
+// the aliases cover the fields that were renamed between them.
+#[derive(Deserialize, Debug)]
+pub struct Idl {
+    #[serde(default)]
+    pub address: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub metadata: Option<IdlMetadata>,
+    pub instructions: Vec<IdlInstruction>,
+    #[serde(default)]
+    pub types: Vec<IdlTypeDef>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct IdlMetadata {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub address: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct IdlInstruction {
+    pub name: String,
+    #[serde(default)]
+    pub discriminator: Option<Vec<u8>>,
+    #[serde(default)]
+    pub accounts: Vec<IdlAccountItem>,
+    #[serde(default)]
+    pub args: Vec<IdlField>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum IdlAccountItem {
+    Composite { accounts: Vec<IdlAccountItem> },
+    Single(IdlAccount),
+}
+
+#[derive(Deserialize, Debug)]
+pub struct IdlAccount {
+    pub name: String,
+    #[serde(default, alias = "isMut")]
+    pub writable: bool,
+    #[serde(default, alias = "isSigner")]
+    pub signer: bool,
+    #[serde(default, alias = "isOptional")]
+    pub optional: bool,
+    #[serde(default)]
+    pub address: Option<String>,
+    #[serde(default)]
+    pub pda: Option<IdlPda>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct IdlPda {
+    pub seeds: Vec<IdlSeed>,
+    #[serde(default, alias = "programId")]
+    pub program: Option<IdlSeed>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum IdlSeed {
+    Const {
+        #[serde(default, rename = "type")]
+        ty: Option<Value>,
+        value: Value,
+    },
+    Arg {
+        #[serde(default, rename = "type")]
+        ty: Option<Value>,
+        path: String,
+    },
+    Account {
+        path: String,
+    },
+}
+
+#[derive(Deserialize, Debug)]
+pub struct IdlField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: Value,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct IdlTypeDef {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: IdlTypeDefTy,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum IdlTypeDefTy {
+    Struct {
+        #[serde(default)]
+        fields: Vec<Value>,
+    },
+    Enum {
+        variants: Vec<IdlEnumVariant>,
+    },
+    Type {
+        alias: Value,
+    },
+}
+
+#[derive(Deserialize, Debug)]
+pub struct IdlEnumVariant {
+    pub name: String,
+    #[serde(default)]
+    pub fields: Vec<Value>,
+}
+
+impl Idl {
+    pub fn program_name(&self) -> Option<&str> {
+        self.metadata
+            .as_ref()
+            .and_then(|m| m.name.as_deref())
+            .or(self.name.as_deref())
+    }
+
+    pub fn program_address(&self) -> Option<&str> {
+        self.address
+            .as_deref()
+            .or(self.metadata.as_ref().and_then(|m| m.address.as_deref()))
+    }
+
+    fn find_instruction(&self, name: &str) -> Option<&IdlInstruction> {
+        let wanted = name.to_snake_case();
+        self.instructions
+            .iter()
+            .find(|ix| ix.name == name || ix.name.to_snake_case() == wanted)
+    }
+
+    fn find_type(&self, name: &str) -> Option<&IdlTypeDef> {
+        self.types.iter().find(|t| t.name == name)
+    }
+}
+
+pub fn idl_dir() -> PathBuf {
+    std::env::var(IDL_DIR_ENV)
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from(DEFAULT_IDL_DIR))
+}
+
+// IDLs are read from the configured directory once at startup, so requests
+// never touch the filesystem. Files that fail to parse are skipped.
+#[derive(Default)]
+pub struct IdlRegistry {
+    idls: Vec<(String, Arc<Idl>)>,
+}
+
+impl IdlRegistry {
+    pub fn load() -> Self {
+        let Ok(entries) = fs::read_dir(idl_dir()) else {
+            return Self::default();
+        };
+
+        let mut idls = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+
+            let Some(stem) = path.file_stem().and_then(|s| s.to_str()).map(str::to_string) else {
+                continue;
+            };
+
+            let contents = match fs::read_to_string(&path) {
+                Ok(c) => c,
+                Err(_) => continue,
+            };
+
+            match serde_json::from_str::<Idl>(&contents) {
+                Ok(idl) => idls.push((stem, Arc::new(idl))),
+                Err(e) => println!("⚠️  Skipping IDL {}: {}", path.display(), e),
+            }
+        }
+
+        idls.sort_by(|a, b| a.0.cmp(&b.0));
+        Self { idls }
+    }
+
+    pub fn len(&self) -> usize {
+        self.idls.len()
+    }
+
+    // Matches `selector` against the file stem, the program name or the
+    // program address of every loaded IDL.
+    pub fn find(&self, selector: &str) -> Result<Arc<Idl>, String> {
+        self.idls
+            .iter()
+            .find(|(stem, idl)| {
+                stem == selector
+                    || idl.program_name() == Some(selector)
+                    || idl.program_address() == Some(selector)
+            })
+            .map(|(_, idl)| idl.clone())
+            .ok_or_else(|| "IDL not found".to_string())
+    }
+}
+
+pub fn instruction_discriminator(ix: &IdlInstruction) -> Result<[u8; 8], String> {
+    match &ix.discriminator {
+        Some(bytes) => <[u8; 8]>::try_from(bytes.as_slice())
+            .map_err(|_| "Invalid IDL: discriminator must be 8 bytes".to_string()),
+        None => {
+            let preimage = format!("global:{}", ix.name.to_snake_case());
+            let mut out = [0u8; 8];
+            out.copy_from_slice(&hash(preimage.as_bytes()).to_bytes()[..8]);
+            Ok(out)
+        }
+    }
+}
+
+pub fn build_instruction(
+    idl: &Idl,
+    program_id: &Pubkey,
+    instruction_name: &str,
+    args: &Map<String, Value>,
+    accounts: &HashMap<String, String>,
+) -> Result<Instruction, String> {
+    let ix = idl
+        .find_instruction(instruction_name)
+        .ok_or_else(|| "Instruction not found in IDL".to_string())?;
+
+    let mut data = instruction_discriminator(ix)?.to_vec();
+    for field in &ix.args {
+        let value =
+            lookup(args, &field.name).ok_or_else(|| format!("Missing argument: {}", field.name))?;
+        encode_value(idl, &field.ty, value, &mut data)
+            .map_err(|e| format!("Invalid argument {}: {}", field.name, e))?;
+    }
+
+    let mut flat = Vec::new();
+    flatten_accounts(&ix.accounts, &mut flat);
+
+    let provided: HashMap<String, Pubkey> = accounts
+        .iter()
+        .map(|(name, key)| {
+            crate::utils::validate_pubkey(key)
+                .map(|pk| (name.to_snake_case(), pk))
+                .map_err(|_| format!("Invalid public key for account: {}", name))
+        })
+        .collect::<Result<_, _>>()?;
+
+    let resolved = resolve_accounts(idl, ix, &flat, program_id, args, &provided)?;
+
+    let metas = flat
+        .iter()
+        .zip(resolved)
+        .map(|(acc, pubkey)| {
+            if acc.writable {
+                AccountMeta::new(pubkey, acc.signer)
+            } else {
+                AccountMeta::new_readonly(pubkey, acc.signer)
+            }
+        })
+        .collect();
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: metas,
+        data,
+    })
+}
+
+fn lookup<'a>(map: &'a Map<String, Value>, name: &str) -> Option<&'a Value> {
+    map.get(name).or_else(|| {
+        let wanted = name.to_snake_case();
+        map.iter()
+            .find(|(k, _)| k.to_snake_case() == wanted)
+            .map(|(_, v)| v)
+    })
+}
+
+fn flatten_accounts<'a>(items: &'a [IdlAccountItem], out: &mut Vec<&'a IdlAccount>) {
+    for item in items {
+        match item {
+            IdlAccountItem::Composite { accounts, .. } => flatten_accounts(accounts, out),
+            IdlAccountItem::Single(acc) => out.push(acc),
+        }
+    }
+}
+
+fn well_known_account(name: &str) -> Option<Pubkey> {
+    match name {
+        "system_program" => Some(solana_sdk::system_program::id()),
+        "token_program" => Some(spl_token::id()),
+        "associated_token_program" => Some(spl_associated_token_account::id()),
+        "rent" => Some(solana_sdk::sysvar::rent::id()),
+        "clock" => Some(solana_sdk::sysvar::clock::id()),
+        "instructions" => Some(solana_sdk::sysvar::instructions::id()),
+        _ => None,
+    }
+}
+
+// PDA seeds may refer to other accounts that are PDAs themselves, so keep
+// resolving until a pass makes no progress.
+fn resolve_accounts(
+    idl: &Idl,
+    ix: &IdlInstruction,
+    flat: &[&IdlAccount],
+    program_id: &Pubkey,
+    args: &Map<String, Value>,
+    provided: &HashMap<String, Pubkey>,
+) -> Result<Vec<Pubkey>, String> {
+    let mut resolved: Vec<Option<Pubkey>> = vec![None; flat.len()];
+    let mut known: HashMap<String, Pubkey> = HashMap::new();
+
+    for (i, acc) in flat.iter().enumerate() {
+        let key = acc.name.to_snake_case();
+        let fixed = match (provided.get(&key), &acc.address) {
+            (Some(pk), _) => Some(*pk),
+            (None, Some(addr)) => Some(
+                Pubkey::from_str(addr)
+                    .map_err(|_| format!("Invalid IDL address for {}", acc.name))?,
+            ),
+            (None, None) if acc.pda.is_none() => well_known_account(&key),
+            _ => None,
+        };
+        if let Some(pk) = fixed {
+            resolved[i] = Some(pk);
+            known.insert(key, pk);
+        }
+    }
+
+    loop {
+        let mut progressed = false;
+        for (i, acc) in flat.iter().enumerate() {
+            if resolved[i].is_some() {
+                continue;
+            }
+            let Some(pda) = &acc.pda else { continue };
+            if let Some(pk) = derive_pda(idl, ix, pda, program_id, args, &known)? {
+                resolved[i] = Some(pk);
+                known.insert(acc.name.to_snake_case(), pk);
+                progressed = true;
+            }
+        }
+        if !progressed {
+            break;
+        }
+    }
+
+    flat.iter()
+        .zip(resolved)
+        .map(|(acc, pk)| match pk {
+            Some(pk) => Ok(pk),
+            // Anchor encodes an omitted optional account as the program id.
+            None if acc.optional => Ok(*program_id),
+            None => Err(format!("Missing account: {}", acc.name)),
+        })
+        .collect()
+}
+
+fn derive_pda(
+    idl: &Idl,
+    ix: &IdlInstruction,
+    pda: &IdlPda,
+    program_id: &Pubkey,
+    args: &Map<String, Value>,
+    known: &HashMap<String, Pubkey>,
+) -> Result<Option<Pubkey>, String> {
+    let mut seeds: Vec<Vec<u8>> = Vec::with_capacity(pda.seeds.len());
+    for seed in &pda.seeds {
+        match seed_bytes(idl, ix, seed, args, known)? {
+            Some(bytes) => seeds.push(bytes),
+            None => return Ok(None),
+        }
+    }
+
+    let owner = match &pda.program {
+        None => *program_id,
+        Some(seed) => match seed_bytes(idl, ix, seed, args, known)? {
+            Some(bytes) => Pubkey::try_from(bytes.as_slice())
+                .map_err(|_| "Invalid IDL: PDA program must be 32 bytes".to_string())?,
+            None => return Ok(None),
+        },
+    };
+
+    let seed_refs: Vec<&[u8]> = seeds.iter().map(|s| s.as_slice()).collect();
+    Pubkey::try_find_program_address(&seed_refs, &owner)
+        .map(|(pk, _)| Some(pk))
+        .ok_or_else(|| "Unable to derive PDA".to_string())
+}
+
+fn seed_bytes(
+    idl: &Idl,
+    ix: &IdlInstruction,
+    seed: &IdlSeed,
+    args: &Map<String, Value>,
+    known: &HashMap<String, Pubkey>,
+) -> Result<Option<Vec<u8>>, String> {
+    match seed {
+        IdlSeed::Const { ty, value } => {
+            // Untyped constants are byte arrays in current IDLs; a bare string
+            // is taken as text.
+            let default_ty = if value.is_string() { "string" } else { "bytes" };
+            let ty = ty
+                .clone()
+                .unwrap_or_else(|| Value::String(default_ty.to_string()));
+            raw_seed_bytes(idl, &ty, value).map(Some)
+        }
+        IdlSeed::Arg { ty, path } => {
+            if path.contains('.') {
+                return Err(format!("Unsupported PDA seed path: {}", path));
+            }
+            let arg_ty = match ty {
+                Some(ty) => ty.clone(),
+                None => ix
+                    .args
+                    .iter()
+                    .find(|a| a.name.to_snake_case() == path.to_snake_case())
+                    .map(|a| a.ty.clone())
+                    .ok_or_else(|| format!("Unknown PDA seed argument: {}", path))?,
+            };
+            let value = lookup(args, path).ok_or_else(|| format!("Missing argument: {}", path))?;
+            raw_seed_bytes(idl, &arg_ty, value).map(Some)
+        }
+        IdlSeed::Account { path } => {
+            if path.contains('.') {
+                return Err(format!("Unsupported PDA seed path: {}", path));
+            }
+            Ok(known
+                .get(&path.to_snake_case())
+                .map(|pk| pk.to_bytes().to_vec()))
+        }
+    }
+}
+
+// Seeds are the value's `as_ref()` bytes, so strings and byte arrays go in
+// without the Borsh length prefix.
+fn raw_seed_bytes(idl: &Idl, ty: &Value, value: &Value) -> Result<Vec<u8>, String> {
+    match (ty.as_str(), value) {
+        (Some("string"), Value::String(s)) => Ok(s.as_bytes().to_vec()),
+        (Some("bytes"), _) => bytes_value(value),
+        (_, Value::Array(items)) if items.iter().all(|v| v.is_u64()) => items
+            .iter()
+            .map(|v| {
+                v.as_u64()
+                    .and_then(|b| u8::try_from(b).ok())
+                    .ok_or_else(|| "Invalid seed byte".to_string())
+            })
+            .collect(),
+        _ => {
+            let mut out = Vec::new();
+            encode_value(idl, ty, value, &mut out)?;
+            Ok(out)
+        }
+    }
+}
+
+pub fn encode_value(idl: &Idl, ty: &Value, value: &Value, out: &mut Vec<u8>) -> Result<(), String> {
+    match ty {
+        Value::String(name) => encode_primitive(name, value, out),
+        Value::Object(obj) => {
+            if let Some(inner) = obj.get("vec") {
+                let items = value.as_array().ok_or("expected an array")?;
+                out.extend_from_slice(&(items.len() as u32).to_le_bytes());
+                for item in items {
+                    encode_value(idl, inner, item, out)?;
+                }
+                Ok(())
+            } else if let Some(inner) = obj.get("option") {
+                if value.is_null() {
+                    out.push(0);
+                    Ok(())
+                } else {
+                    out.push(1);
+                    encode_value(idl, inner, value, out)
+                }
+            } else if let Some(inner) = obj.get("coption") {
+                if value.is_null() {
+                    out.extend_from_slice(&0u32.to_le_bytes());
+                    Ok(())
+                } else {
+                    out.extend_from_slice(&1u32.to_le_bytes());
+                    encode_value(idl, inner, value, out)
+                }
+            } else if let Some(array) = obj.get("array") {
+                let (inner, len) = match array.as_array().map(|a| a.as_slice()) {
+                    Some([inner, len]) => (inner, len.as_u64().ok_or("unsupported array length")?),
+                    _ => return Err("invalid array type".to_string()),
+                };
+                let items = value.as_array().ok_or("expected an array")?;
+                if items.len() as u64 != len {
+                    return Err(format!("expected {} elements", len));
+                }
+                for item in items {
+                    encode_value(idl, inner, item, out)?;
+                }
+                Ok(())
+            } else if let Some(defined) = obj.get("defined") {
+                let name = match defined {
+                    Value::String(name) => name.as_str(),
+                    Value::Object(d) => d
+                        .get("name")
+                        .and_then(|n| n.as_str())
+                        .ok_or("invalid defined type")?,
+                    _ => return Err("invalid defined type".to_string()),
+                };
+                encode_defined(idl, name, value, out)
+            } else {
+                Err("unsupported type".to_string())
+            }
+        }
+        _ => Err("unsupported type".to_string()),
+    }
+}
+
+fn encode_defined(idl: &Idl, name: &str, value: &Value, out: &mut Vec<u8>) -> Result<(), String> {
+    let def = idl
+        .find_type(name)
+        .ok_or_else(|| format!("unknown type {}", name))?;
+
+    match &def.ty {
+        IdlTypeDefTy::Struct { fields } => encode_fields(idl, fields, value, out),
+        IdlTypeDefTy::Type { alias } => encode_value(idl, alias, value, out),
+        IdlTypeDefTy::Enum { variants } => {
+            let (variant_name, fields_value) = match value {
+                Value::String(s) => (s.as_str(), None),
+                Value::Object(o) if o.len() == 1 => {
+                    let (k, v) = o.iter().next().expect("checked length");
+                    (k.as_str(), Some(v))
+                }
+                _ => return Err(format!("expected a variant of {}", name)),
+            };
+            let index = variants
+                .iter()
+                .position(|v| v.name == variant_name)
+                .ok_or_else(|| format!("unknown variant {}", variant_name))?;
+            out.push(u8::try_from(index).map_err(|_| "too many enum variants")?);
+            let variant = &variants[index];
+            match fields_value {
+                Some(v) => encode_fields(idl, &variant.fields, v, out),
+                None if variant.fields.is_empty() => Ok(()),
+                None => Err(format!("missing fields for variant {}", variant_name)),
+            }
+        }
+    }
+}
+
+// Named fields are `{name, type}` objects read from a JSON object; tuple
+// fields are bare types read positionally from a JSON array.
+fn encode_fields(
+    idl: &Idl,
+    fields: &[Value],
+    value: &Value,
+    out: &mut Vec<u8>,
+) -> Result<(), String> {
+    for (i, field) in fields.iter().enumerate() {
+        match field.get("name").and_then(|n| n.as_str()) {
+            Some(name) => {
+                let ty = field.get("type").ok_or("invalid field definition")?;
+                let obj = value.as_object().ok_or("expected an object")?;
+                let v = lookup(obj, name).ok_or_else(|| format!("missing field {}", name))?;
+                encode_value(idl, ty, v, out)?;
+            }
+            None => {
+                let items = value.as_array().ok_or("expected an array")?;
+                let v = items.get(i).ok_or("not enough tuple elements")?;
+                encode_value(idl, field, v, out)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn encode_primitive(name: &str, value: &Value, out: &mut Vec<u8>) -> Result<(), String> {
+    match name {
+        "bool" => out.push(value.as_bool().ok_or("expected a boolean")? as u8),
+        "u8" => out.push(parse_int::<u8>(value)?),
+        "i8" => out.extend_from_slice(&parse_int::<i8>(value)?.to_le_bytes()),
+        "u16" => out.extend_from_slice(&parse_int::<u16>(value)?.to_le_bytes()),
+        "i16" => out.extend_from_slice(&parse_int::<i16>(value)?.to_le_bytes()),
+        "u32" => out.extend_from_slice(&parse_int::<u32>(value)?.to_le_bytes()),
+        "i32" => out.extend_from_slice(&parse_int::<i32>(value)?.to_le_bytes()),
+        "u64" => out.extend_from_slice(&parse_int::<u64>(value)?.to_le_bytes()),
+        "i64" => out.extend_from_slice(&parse_int::<i64>(value)?.to_le_bytes()),
+        "u128" => out.extend_from_slice(&parse_int::<u128>(value)?.to_le_bytes()),
+        "i128" => out.extend_from_slice(&parse_int::<i128>(value)?.to_le_bytes()),
+        "f32" => out
+            .extend_from_slice(&(value.as_f64().ok_or("expected a number")? as f32).to_le_bytes()),
+        "f64" => out.extend_from_slice(&value.as_f64().ok_or("expected a number")?.to_le_bytes()),
+        "string" => {
+            let s = value.as_str().ok_or("expected a string")?;
+            out.extend_from_slice(&(s.len() as u32).to_le_bytes());
+            out.extend_from_slice(s.as_bytes());
+        }
+        "publicKey" | "pubkey" => {
+            let s = value.as_str().ok_or("expected a public key")?;
+            let pk = Pubkey::from_str(s).map_err(|_| "Invalid public key")?;
+            out.extend_from_slice(pk.as_ref());
+        }
+        "bytes" => {
+            let bytes = bytes_value(value)?;
+            out.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+            out.extend_from_slice(&bytes);
+        }
+        other => return Err(format!("unsupported type {}", other)),
+    }
+    Ok(())
+}
+
+// Byte values are a number array or a base64 string, decoded the same way for
+// instruction data and PDA seeds.
+fn bytes_value(value: &Value) -> Result<Vec<u8>, String> {
+    match value {
+        Value::Array(items) => items.iter().map(parse_int::<u8>).collect(),
+        Value::String(s) => {
+            use base64::{Engine as _, engine::general_purpose};
+            general_purpose::STANDARD
+                .decode(s)
+                .map_err(|_| "expected base64 bytes".to_string())
+        }
+        _ => Err("expected bytes".to_string()),
+    }
+}
+
+// Large integers arrive as strings since JSON numbers lose precision past 2^53.
+fn parse_int<T: FromStr>(value: &Value) -> Result<T, String> {
+    let text = match value {
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => s.trim().to_string(),
+        _ => return Err("expected an integer".to_string()),
+    };
+    text.parse::<T>()
+        .map_err(|_| "integer out of range".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn bytes_seeded_idl() -> Idl {
+        serde_json::from_value(json!({
+            "address": "11111111111111111111111111111111",
+            "metadata": { "name": "notes" },
+            "instructions": [{
+                "name": "store",
+                "discriminator": [1, 2, 3, 4, 5, 6, 7, 8],
+                "accounts": [{
+                    "name": "note",
+                    "writable": true,
+                    "pda": {
+                        "seeds": [
+                            { "kind": "const", "value": [110, 111, 116, 101] },
+                            { "kind": "arg", "path": "data" },
+                        ],
+                    },
+                }],
+                "args": [{ "name": "data", "type": "bytes" }],
+            }],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn bytes_seed_matches_the_encoded_argument() {
+        let idl = bytes_seeded_idl();
+        let program_id = Pubkey::new_unique();
+        let expected = Pubkey::find_program_address(&[b"note", &[1, 2, 3]], &program_id).0;
+
+        for data in [json!("AQID"), json!([1, 2, 3])] {
+            let args = json!({ "data": data });
+            let ix = build_instruction(
+                &idl,
+                &program_id,
+                "store",
+                args.as_object().unwrap(),
+                &HashMap::new(),
+            )
+            .unwrap();
+
+            assert_eq!(ix.data[8..], [3, 0, 0, 0, 1, 2, 3]);
+            assert_eq!(ix.accounts[0].pubkey, expected);
+        }
+    }
+}
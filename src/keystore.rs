@@ -0,0 +1,1301 @@
+use aes_gcm::{
+    Aes256Gcm, Nonce,
+    aead::{Aead, KeyInit, Payload},
+};
+use argon2::{Algorithm, Argon2, Params, Version};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    message::VersionedMessage,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    signer::keypair::keypair_from_seed,
+};
+use std::{
+    collections::HashMap,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+use tokio::sync::RwLock;
+use zeroize::Zeroizing;
+
+use crate::{
+    policy::{DailySpend, SigningPolicy, SigningRequest, Spend, check_message},
+    utils::{parse_secret_key, random_hex, unix_now},
+};
+
+pub const KEYSTORE_DIR_ENV: &str = "KEYSTORE_DIR";
+pub const KEYSTORE_MASTER_KEY_ENV: &str = "KEYSTORE_MASTER_KEY";
+
+const DEFAULT_KEYSTORE_DIR: &str = "keystore";
+const KDF_ALGORITHM: &str = "argon2id";
+const KDF_MEMORY_KIB: u32 = 19 * 1024;
+const KDF_ITERATIONS: u32 = 2;
+const KDF_PARALLELISM: u32 = 1;
+const CIPHER_ALGORITHM: &str = "aes-256-gcm";
+const MAX_LABEL_LEN: usize = 64;
+const AUDIT_LOG_FILE: &str = "audit.log";
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Protection {
+    Password,
+    Master,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct KdfParams {
+    pub algorithm: String,
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+    pub salt: String,
+}
+
+// On-disk format of a key file. Only the 32-byte ed25519 seed is encrypted;
+// the public key is bound in as associated data so files cannot be swapped.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct StoredKey {
+    pub id: String,
+    pub label: Option<String>,
+    pub pubkey: String,
+    pub protection: Protection,
+    pub created_at: u64,
+    #[serde(default)]
+    pub disabled: bool,
+    #[serde(default)]
+    pub last_used_at: Option<u64>,
+    #[serde(default)]
+    pub rotated_from: Option<String>,
+    #[serde(default)]
+    pub rotated_to: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy: Option<SigningPolicy>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub daily_spend: Option<DailySpend>,
+    pub kdf: KdfParams,
+    pub cipher: String,
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+// A transaction the key's policy holds back until enough principals approve.
+pub struct ApprovalRequirement {
+    pub stored: StoredKey,
+    pub quorum: u32,
+    pub spend: Spend,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AuditEntry {
+    pub timestamp: u64,
+    pub action: String,
+    // Principal that asked for the action, when the request carried an API key.
+    #[serde(default)]
+    pub actor: Option<String>,
+    pub key_id: String,
+    pub pubkey: String,
+    pub label: Option<String>,
+    pub detail: Option<String>,
+}
+
+pub struct Keystore {
+    dir: PathBuf,
+    master_key: Option<Zeroizing<String>>,
+    keys: RwLock<HashMap<String, StoredKey>>,
+}
+
+fn derive_key(passphrase: &str, kdf: &KdfParams) -> Result<Zeroizing<[u8; 32]>, String> {
+    if kdf.algorithm != KDF_ALGORITHM {
+        return Err(format!("Unsupported key derivation: {}", kdf.algorithm));
+    }
+
+    let salt = hex::decode(&kdf.salt).map_err(|_| "Corrupt key file: invalid salt".to_string())?;
+    let params = Params::new(kdf.memory_kib, kdf.iterations, kdf.parallelism, Some(32))
+        .map_err(|e| format!("Invalid key derivation parameters: {}", e))?;
+
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), &salt, key.as_mut())
+        .map_err(|e| format!("Key derivation failed: {}", e))?;
+    Ok(key)
+}
+
+fn encrypt_seed(
+    keypair: &Keypair,
+    passphrase: &str,
+) -> Result<(KdfParams, String, String), String> {
+    let mut salt = [0u8; 16];
+    let mut nonce = [0u8; 12];
+    rand::thread_rng().fill_bytes(&mut salt);
+    rand::thread_rng().fill_bytes(&mut nonce);
+
+    let kdf = KdfParams {
+        algorithm: KDF_ALGORITHM.to_string(),
+        memory_kib: KDF_MEMORY_KIB,
+        iterations: KDF_ITERATIONS,
+        parallelism: KDF_PARALLELISM,
+        salt: hex::encode(salt),
+    };
+
+    let key = derive_key(passphrase, &kdf)?;
+    let cipher = Aes256Gcm::new_from_slice(key.as_ref()).map_err(|e| e.to_string())?;
+    let bytes = Zeroizing::new(keypair.to_bytes());
+    let pubkey = keypair.pubkey();
+
+    let ciphertext = cipher
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: &bytes[..32],
+                aad: pubkey.as_ref(),
+            },
+        )
+        .map_err(|_| "Encryption failed".to_string())?;
+
+    Ok((kdf, hex::encode(nonce), hex::encode(ciphertext)))
+}
+
+fn decrypt_seed(stored: &StoredKey, passphrase: &str) -> Result<Keypair, String> {
+    if stored.cipher != CIPHER_ALGORITHM {
+        return Err(format!("Unsupported cipher: {}", stored.cipher));
+    }
+
+    let nonce = hex::decode(&stored.nonce)
+        .ok()
+        .filter(|n| n.len() == 12)
+        .ok_or_else(|| "Corrupt key file: invalid nonce".to_string())?;
+    let ciphertext = hex::decode(&stored.ciphertext)
+        .map_err(|_| "Corrupt key file: invalid ciphertext".to_string())?;
+    let pubkey = bs58::decode(&stored.pubkey)
+        .into_vec()
+        .map_err(|_| "Corrupt key file: invalid pubkey".to_string())?;
+
+    let key = derive_key(passphrase, &stored.kdf)?;
+    let cipher = Aes256Gcm::new_from_slice(key.as_ref()).map_err(|e| e.to_string())?;
+
+    let seed = cipher
+        .decrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: &ciphertext,
+                aad: &pubkey,
+            },
+        )
+        .map(Zeroizing::new)
+        .map_err(|_| "Invalid password".to_string())?;
+
+    let keypair = keypair_from_seed(&seed).map_err(|_| "Corrupt key file".to_string())?;
+    if keypair.pubkey().to_string() != stored.pubkey {
+        return Err("Corrupt key file: public key mismatch".to_string());
+    }
+    Ok(keypair)
+}
+
+fn validate_label(label: &str) -> Result<String, String> {
+    let label = label.trim();
+    if label.is_empty() || label.len() > MAX_LABEL_LEN {
+        return Err(format!(
+            "Invalid label: must be between 1 and {} characters",
+            MAX_LABEL_LEN
+        ));
+    }
+    if !label
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        return Err("Invalid label: use letters, digits, '-', '_' or '.'".to_string());
+    }
+    Ok(label.to_string())
+}
+
+fn write_key_file(dir: &Path, stored: &StoredKey) -> Result<(), String> {
+    let contents = serde_json::to_vec_pretty(stored).map_err(|e| e.to_string())?;
+    let path = dir.join(format!("{}.json", stored.id));
+    let tmp = dir.join(format!(".{}.json.tmp", stored.id));
+
+    fs::write(&tmp, contents).map_err(|e| format!("Failed to write key file: {}", e))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(&tmp, fs::Permissions::from_mode(0o600));
+    }
+    fs::rename(&tmp, &path).map_err(|e| format!("Failed to write key file: {}", e))
+}
+
+// Overwrites the file in place before unlinking it. Filesystems with
+// copy-on-write or journaling may still hold old blocks, so this is a best
+// effort on top of the secret already being encrypted.
+fn shred_file(path: &Path) -> Result<(), String> {
+    let len = fs::metadata(path)
+        .map_err(|e| format!("Failed to delete key file: {}", e))?
+        .len() as usize;
+
+    let mut noise = vec![0u8; len];
+    rand::thread_rng().fill_bytes(&mut noise);
+
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .open(path)
+        .map_err(|e| format!("Failed to delete key file: {}", e))?;
+    file.write_all(&noise)
+        .and_then(|_| file.sync_all())
+        .map_err(|e| format!("Failed to delete key file: {}", e))?;
+    drop(file);
+
+    fs::remove_file(path).map_err(|e| format!("Failed to delete key file: {}", e))
+}
+
+impl Keystore {
+    // Keys live one per file under `KEYSTORE_DIR`. Keys created without a
+    // password are sealed with `KEYSTORE_MASTER_KEY`, which must then be set
+    // for as long as they are in use.
+    pub fn load() -> Result<Self, String> {
+        let dir = PathBuf::from(
+            std::env::var(KEYSTORE_DIR_ENV).unwrap_or_else(|_| DEFAULT_KEYSTORE_DIR.to_string()),
+        );
+        let master_key = std::env::var(KEYSTORE_MASTER_KEY_ENV)
+            .ok()
+            .filter(|k| !k.is_empty())
+            .map(Zeroizing::new);
+
+        Self::open(dir, master_key)
+    }
+
+    fn open(dir: PathBuf, master_key: Option<Zeroizing<String>>) -> Result<Self, String> {
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create keystore {}: {}", dir.display(), e))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let _ = fs::set_permissions(&dir, fs::Permissions::from_mode(0o700));
+        }
+
+        let mut keys = HashMap::new();
+        let entries = fs::read_dir(&dir)
+            .map_err(|e| format!("Failed to read keystore {}: {}", dir.display(), e))?;
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            let contents = fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            let stored: StoredKey = serde_json::from_str(&contents)
+                .map_err(|e| format!("Invalid key file {}: {}", path.display(), e))?;
+            keys.insert(stored.id.clone(), stored);
+        }
+
+        Ok(Self {
+            dir,
+            master_key,
+            keys: RwLock::new(keys),
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub async fn key_count(&self) -> usize {
+        self.keys.read().await.len()
+    }
+
+    fn passphrase_for(
+        &self,
+        protection: Protection,
+        password: Option<String>,
+    ) -> Result<Zeroizing<String>, String> {
+        match protection {
+            Protection::Password => match password {
+                Some(password) if !password.is_empty() => Ok(Zeroizing::new(password)),
+                _ => Err("Missing required fields: password".to_string()),
+            },
+            Protection::Master => self
+                .master_key
+                .clone()
+                .ok_or_else(|| "Keystore master key not configured".to_string()),
+        }
+    }
+
+    fn protection_for(&self, password: &Option<String>) -> Result<Protection, String> {
+        match password {
+            Some(password) if !password.is_empty() => Ok(Protection::Password),
+            Some(_) => Err("Invalid password: must not be empty".to_string()),
+            None if self.master_key.is_some() => Ok(Protection::Master),
+            None => Err(
+                "Missing required fields: password (no keystore master key configured)".to_string(),
+            ),
+        }
+    }
+
+    async fn seal(
+        &self,
+        keypair: Keypair,
+        label: Option<String>,
+        protection: Protection,
+        passphrase: Zeroizing<String>,
+        rotated_from: Option<String>,
+    ) -> Result<StoredKey, String> {
+        let pubkey = keypair.pubkey().to_string();
+
+        let (kdf, nonce, ciphertext) =
+            tokio::task::spawn_blocking(move || encrypt_seed(&keypair, &passphrase))
+                .await
+                .map_err(|e| e.to_string())??;
+
+        Ok(StoredKey {
+            id: random_hex(16),
+            label,
+            pubkey,
+            protection,
+            created_at: unix_now(),
+            disabled: false,
+            last_used_at: None,
+            rotated_from,
+            rotated_to: None,
+            policy: None,
+            daily_spend: None,
+            kdf,
+            cipher: CIPHER_ALGORITHM.to_string(),
+            nonce,
+            ciphertext,
+        })
+    }
+
+    fn record(
+        &self,
+        action: &str,
+        stored: &StoredKey,
+        actor: Option<&str>,
+        detail: Option<String>,
+    ) {
+        let entry = AuditEntry {
+            timestamp: unix_now(),
+            action: action.to_string(),
+            actor: actor.map(str::to_string),
+            key_id: stored.id.clone(),
+            pubkey: stored.pubkey.clone(),
+            label: stored.label.clone(),
+            detail,
+        };
+
+        let Ok(mut line) = serde_json::to_string(&entry) else {
+            return;
+        };
+        line.push('\n');
+
+        let written = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.dir.join(AUDIT_LOG_FILE))
+            .and_then(|mut file| file.write_all(line.as_bytes()));
+
+        if let Err(e) = written {
+            println!("⚠️  Failed to write keystore audit entry: {}", e);
+        }
+    }
+
+    // Proves the caller holds the key's passphrase without keeping the
+    // decrypted seed around. Failures are audited like a failed unlock.
+    async fn verify_passphrase(
+        &self,
+        stored: &StoredKey,
+        passphrase: Zeroizing<String>,
+        actor: Option<&str>,
+    ) -> Result<(), String> {
+        let sealed = stored.clone();
+        match tokio::task::spawn_blocking(move || decrypt_seed(&sealed, &passphrase))
+            .await
+            .map_err(|e| e.to_string())?
+        {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                self.record("unlock_failed", stored, actor, Some(e.clone()));
+                Err(e)
+            }
+        }
+    }
+
+    pub async fn create(
+        &self,
+        keypair: Keypair,
+        label: Option<String>,
+        password: Option<String>,
+        imported: bool,
+        actor: Option<&str>,
+    ) -> Result<StoredKey, String> {
+        let label = match label {
+            Some(label) => Some(validate_label(&label)?),
+            None => None,
+        };
+
+        let protection = self.protection_for(&password)?;
+        let passphrase = self.passphrase_for(protection, password)?;
+
+        let pubkey = keypair.pubkey().to_string();
+        {
+            let keys = self.keys.read().await;
+            if let Some(existing) = keys.values().find(|k| k.pubkey == pubkey) {
+                return Err(format!("Key already stored as {}", existing.id));
+            }
+            if let Some(label) = &label
+                && keys.values().any(|k| k.label.as_ref() == Some(label))
+            {
+                return Err(format!("Label already in use: {}", label));
+            }
+        }
+
+        let stored = self.seal(keypair, label, protection, passphrase, None).await?;
+
+        let mut keys = self.keys.write().await;
+        if let Some(label) = &stored.label
+            && keys.values().any(|k| k.label.as_ref() == Some(label))
+        {
+            return Err(format!("Label already in use: {}", label));
+        }
+        write_key_file(&self.dir, &stored)?;
+        keys.insert(stored.id.clone(), stored.clone());
+        drop(keys);
+
+        self.record(if imported { "import" } else { "create" }, &stored, actor, None);
+        Ok(stored)
+    }
+
+    pub async fn list(&self) -> Vec<StoredKey> {
+        let mut keys: Vec<StoredKey> = self.keys.read().await.values().cloned().collect();
+        keys.sort_by_key(|k| k.created_at);
+        keys
+    }
+
+    // Keys are referenced by id or, failing that, by label.
+    pub async fn resolve(&self, key_ref: &str) -> Option<StoredKey> {
+        let key_ref = key_ref.trim();
+        let keys = self.keys.read().await;
+        keys.get(key_ref)
+            .or_else(|| keys.values().find(|k| k.label.as_deref() == Some(key_ref)))
+            .cloned()
+    }
+
+    pub async fn unlock(
+        &self,
+        key_ref: &str,
+        password: Option<String>,
+        actor: Option<&str>,
+        request: &SigningRequest<'_>,
+    ) -> Result<Keypair, String> {
+        let stored = self
+            .resolve(key_ref)
+            .await
+            .ok_or_else(|| "Key not found".to_string())?;
+
+        if stored.disabled {
+            self.record("use_denied", &stored, actor, Some("key is disabled".to_string()));
+            return Err("Key is disabled".to_string());
+        }
+
+        // Anyone can list key ids, so a master-sealed key, which has no
+        // password of its own, only signs for an authenticated principal.
+        if stored.protection == Protection::Master && actor.is_none() {
+            self.record("use_denied", &stored, None, Some("no principal".to_string()));
+            return Err("API key required".to_string());
+        }
+
+        let passphrase = self.passphrase_for(stored.protection, password)?;
+        let sealed = stored.clone();
+
+        let keypair = match tokio::task::spawn_blocking(move || decrypt_seed(&sealed, &passphrase))
+            .await
+            .map_err(|e| e.to_string())?
+        {
+            Ok(kp) => kp,
+            Err(e) => {
+                self.record("unlock_failed", &stored, actor, Some(e.clone()));
+                return Err(e);
+            }
+        };
+
+        let updated = self.authorize(&stored.id, &keypair.pubkey(), request).await?;
+        self.record("use", &updated, actor, None);
+
+        Ok(keypair)
+    }
+
+    // Checks the key's policy and reserves today's spend in the same critical
+    // section, so concurrent requests cannot jointly overrun a daily limit.
+    async fn authorize(
+        &self,
+        id: &str,
+        signer: &Pubkey,
+        request: &SigningRequest<'_>,
+    ) -> Result<StoredKey, String> {
+        let mut keys = self.keys.write().await;
+        let stored = keys.get_mut(id).ok_or_else(|| "Key not found".to_string())?;
+        let now = unix_now();
+
+        if let Some(policy) = &stored.policy {
+            let verdict = policy.check_time(now).and_then(|_| match request {
+                SigningRequest::Message(bytes) => check_message(bytes).map(|_| None),
+                SigningRequest::Transaction(message) => {
+                    let spend = policy.evaluate(signer, message)?;
+                    if policy.requires_approval(&spend) {
+                        return Err("transaction requires approval".to_string());
+                    }
+                    policy
+                        .apply_daily(stored.daily_spend.as_ref(), &spend, now)
+                        .map(Some)
+                }
+                SigningRequest::ApprovedTransaction(message) => {
+                    let spend = policy.evaluate(signer, message)?;
+                    policy
+                        .apply_daily(stored.daily_spend.as_ref(), &spend, now)
+                        .map(Some)
+                }
+            });
+
+            match verdict {
+                Ok(Some(daily)) => stored.daily_spend = Some(daily),
+                Ok(None) => {}
+                Err(reason) => {
+                    let rejected = stored.clone();
+                    drop(keys);
+                    self.record("policy_rejected", &rejected, None, Some(reason.clone()));
+                    return Err(format!("Policy violation: {}", reason));
+                }
+            }
+        }
+
+        stored.last_used_at = Some(now);
+        write_key_file(&self.dir, stored)?;
+        Ok(stored.clone())
+    }
+
+    // Returns the quorum and spend when the key's policy parks this
+    // transaction for approval. Other policy violations are reported right
+    // away instead of being queued.
+    pub async fn approval_requirement(
+        &self,
+        key_ref: &str,
+        message: &VersionedMessage,
+    ) -> Result<Option<ApprovalRequirement>, String> {
+        let stored = self
+            .resolve(key_ref)
+            .await
+            .ok_or_else(|| "Key not found".to_string())?;
+
+        if stored.disabled {
+            return Err("Key is disabled".to_string());
+        }
+
+        let Some(policy) = &stored.policy else {
+            return Ok(None);
+        };
+
+        let signer = Pubkey::from_str(&stored.pubkey).map_err(|_| "Corrupt key file".to_string())?;
+        let spend = policy
+            .evaluate(&signer, message)
+            .map_err(|reason| format!("Policy violation: {}", reason))?;
+
+        if !policy.requires_approval(&spend) {
+            return Ok(None);
+        }
+
+        let quorum = policy.quorum();
+        Ok(Some(ApprovalRequirement {
+            stored,
+            quorum,
+            spend,
+        }))
+    }
+
+    pub fn record_event(
+        &self,
+        stored: &StoredKey,
+        action: &str,
+        actor: Option<&str>,
+        detail: Option<String>,
+    ) {
+        self.record(action, stored, actor, detail);
+    }
+
+    // Callers authenticate first and route loosening changes through the
+    // approval queue; this only validates and stores the policy.
+    pub async fn set_policy(
+        &self,
+        key_ref: &str,
+        policy: Option<SigningPolicy>,
+        actor: Option<&str>,
+    ) -> Result<StoredKey, String> {
+        if let Some(policy) = &policy {
+            policy.validate()?;
+        }
+
+        let stored = self
+            .resolve(key_ref)
+            .await
+            .ok_or_else(|| "Key not found".to_string())?;
+
+        let updated = {
+            let mut keys = self.keys.write().await;
+            let entry = keys
+                .get_mut(&stored.id)
+                .ok_or_else(|| "Key not found".to_string())?;
+            entry.policy = policy;
+            write_key_file(&self.dir, entry)?;
+            entry.clone()
+        };
+
+        let action = if updated.policy.is_some() {
+            "policy_set"
+        } else {
+            "policy_cleared"
+        };
+        self.record(action, &updated, actor, None);
+        Ok(updated)
+    }
+
+    pub async fn update(
+        &self,
+        key_ref: &str,
+        label: Option<String>,
+        disabled: Option<bool>,
+        password: Option<String>,
+        actor: Option<&str>,
+    ) -> Result<StoredKey, String> {
+        let stored = self
+            .resolve(key_ref)
+            .await
+            .ok_or_else(|| "Key not found".to_string())?;
+
+        self.authenticate(&stored, password, actor).await?;
+
+        let label = match label {
+            Some(label) => Some(validate_label(&label)?),
+            None => None,
+        };
+
+        if disabled == Some(false) && stored.rotated_to.is_some() {
+            return Err("Key was rotated and cannot be re-enabled".to_string());
+        }
+
+        let (before, updated) = {
+            let mut keys = self.keys.write().await;
+            if let Some(label) = &label
+                && keys
+                    .values()
+                    .any(|k| k.id != stored.id && k.label.as_ref() == Some(label))
+            {
+                return Err(format!("Label already in use: {}", label));
+            }
+
+            let before = keys
+                .get(&stored.id)
+                .cloned()
+                .ok_or_else(|| "Key not found".to_string())?;
+            let mut updated = before.clone();
+            if let Some(label) = label {
+                updated.label = Some(label);
+            }
+            if let Some(disabled) = disabled {
+                updated.disabled = disabled;
+            }
+
+            write_key_file(&self.dir, &updated)?;
+            keys.insert(updated.id.clone(), updated.clone());
+            (before, updated)
+        };
+
+        if updated.label != before.label {
+            self.record(
+                "rename",
+                &updated,
+                actor,
+                Some(format!(
+                    "{} -> {}",
+                    before.label.as_deref().unwrap_or("-"),
+                    updated.label.as_deref().unwrap_or("-")
+                )),
+            );
+        }
+        if updated.disabled != before.disabled {
+            let action = if updated.disabled { "disable" } else { "enable" };
+            self.record(action, &updated, actor, None);
+        }
+
+        Ok(updated)
+    }
+
+    // Generates a replacement under the same kind of protection. The new key
+    // takes over the label; the old one is disabled and points at its
+    // successor so the lineage can be followed either way.
+    pub async fn rotate(
+        &self,
+        key_ref: &str,
+        password: Option<String>,
+        new_password: Option<String>,
+        actor: Option<&str>,
+    ) -> Result<(StoredKey, StoredKey), String> {
+        let stored = self
+            .resolve(key_ref)
+            .await
+            .ok_or_else(|| "Key not found".to_string())?;
+
+        if stored.rotated_to.is_some() {
+            return Err("Key was already rotated".to_string());
+        }
+
+        self.authenticate(&stored, password.clone(), actor).await?;
+        let passphrase = self.passphrase_for(stored.protection, password)?;
+
+        let new_passphrase = match (stored.protection, new_password) {
+            (Protection::Password, Some(new_password)) if !new_password.is_empty() => {
+                Zeroizing::new(new_password)
+            }
+            (Protection::Password, Some(_)) => {
+                return Err("Invalid newPassword: must not be empty".to_string());
+            }
+            (Protection::Password, None) => passphrase,
+            (Protection::Master, Some(_)) => {
+                return Err("Invalid newPassword: key is protected by the master key".to_string());
+            }
+            (Protection::Master, None) => passphrase,
+        };
+
+        let mut replacement = self
+            .seal(
+                Keypair::new(),
+                None,
+                stored.protection,
+                new_passphrase,
+                Some(stored.id.clone()),
+            )
+            .await?;
+
+        let mut keys = self.keys.write().await;
+        let mut retired = keys
+            .get(&stored.id)
+            .cloned()
+            .ok_or_else(|| "Key not found".to_string())?;
+        if retired.rotated_to.is_some() {
+            return Err("Key was already rotated".to_string());
+        }
+
+        replacement.label = retired.label.take();
+        replacement.policy = retired.policy.clone();
+        replacement.daily_spend = retired.daily_spend.clone();
+        retired.disabled = true;
+        retired.rotated_to = Some(replacement.id.clone());
+
+        write_key_file(&self.dir, &replacement)?;
+        write_key_file(&self.dir, &retired)?;
+        keys.insert(replacement.id.clone(), replacement.clone());
+        keys.insert(retired.id.clone(), retired.clone());
+        drop(keys);
+
+        self.record("rotate", &retired, actor, Some(format!("replaced by {}", replacement.id)));
+        self.record("create", &replacement, actor, Some(format!("rotated from {}", retired.id)));
+
+        Ok((retired, replacement))
+    }
+
+    // Checks the caller may manage the key. A password-protected key needs
+    // its password; keys sealed with the master key have no secret of their
+    // own to prove, so an authenticated principal has to ask instead.
+    pub async fn authenticate(
+        &self,
+        stored: &StoredKey,
+        password: Option<String>,
+        actor: Option<&str>,
+    ) -> Result<(), String> {
+        match stored.protection {
+            Protection::Password => {
+                let passphrase = self.passphrase_for(Protection::Password, password)?;
+                self.verify_passphrase(stored, passphrase, actor).await
+            }
+            Protection::Master if actor.is_none() => Err("API key required".to_string()),
+            Protection::Master => Ok(()),
+        }
+    }
+
+    pub async fn delete(
+        &self,
+        key_ref: &str,
+        password: Option<String>,
+        actor: Option<&str>,
+    ) -> Result<StoredKey, String> {
+        let stored = self
+            .resolve(key_ref)
+            .await
+            .ok_or_else(|| "Key not found".to_string())?;
+
+        self.authenticate(&stored, password, actor).await?;
+
+        let mut keys = self.keys.write().await;
+        shred_file(&self.dir.join(format!("{}.json", stored.id)))?;
+        let removed = keys.remove(&stored.id).unwrap_or(stored);
+        drop(keys);
+
+        self.record("delete", &removed, actor, None);
+        Ok(removed)
+    }
+
+    pub fn audit_trail(&self, key_id: Option<&str>, limit: usize) -> Result<Vec<AuditEntry>, String> {
+        let contents = match fs::read_to_string(self.dir.join(AUDIT_LOG_FILE)) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(format!("Failed to read audit trail: {}", e)),
+        };
+
+        let mut entries: Vec<AuditEntry> = contents
+            .lines()
+            .filter_map(|line| serde_json::from_str::<AuditEntry>(line).ok())
+            .filter(|entry| key_id.is_none_or(|id| entry.key_id == id))
+            .collect();
+
+        let skip = entries.len().saturating_sub(limit);
+        Ok(entries.split_off(skip))
+    }
+
+    // Shared by every signing route: exactly one of an inline `secret` or a
+    // stored `keyId` picks the signer.
+    pub async fn signer(
+        &self,
+        secret: Option<String>,
+        secret_format: Option<&str>,
+        key_id: Option<String>,
+        password: Option<String>,
+        actor: Option<&str>,
+        request: SigningRequest<'_>,
+    ) -> Result<Keypair, String> {
+        let secret = secret.filter(|s| !s.trim().is_empty());
+        let key_id = key_id.filter(|k| !k.trim().is_empty());
+
+        match (secret, key_id) {
+            (Some(secret), None) => parse_secret_key(&secret, secret_format),
+            (None, Some(key_id)) => self.unlock(&key_id, password, actor, &request).await,
+            (Some(_), Some(_)) => Err("Provide either secret or keyId, not both".to_string()),
+            (None, None) => Err("Missing required fields".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sealed(keypair: &Keypair, passphrase: &str) -> StoredKey {
+        let (kdf, nonce, ciphertext) = encrypt_seed(keypair, passphrase).unwrap();
+        StoredKey {
+            id: random_hex(16),
+            label: None,
+            pubkey: keypair.pubkey().to_string(),
+            protection: Protection::Password,
+            created_at: unix_now(),
+            disabled: false,
+            last_used_at: None,
+            rotated_from: None,
+            rotated_to: None,
+            policy: None,
+            daily_spend: None,
+            kdf,
+            cipher: CIPHER_ALGORITHM.to_string(),
+            nonce,
+            ciphertext,
+        }
+    }
+
+    struct TempKeystore {
+        keystore: Keystore,
+    }
+
+    impl TempKeystore {
+        fn open(master_key: Option<&str>) -> Self {
+            let dir = std::env::temp_dir().join(format!("keystore-test-{}", random_hex(8)));
+            let master_key = master_key.map(|k| Zeroizing::new(k.to_string()));
+            Self {
+                keystore: Keystore::open(dir, master_key).unwrap(),
+            }
+        }
+    }
+
+    impl Drop for TempKeystore {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(self.keystore.dir());
+        }
+    }
+
+    fn message() -> SigningRequest<'static> {
+        SigningRequest::Message(b"hello")
+    }
+
+    fn audit_actions(keystore: &Keystore) -> Vec<String> {
+        keystore
+            .audit_trail(None, 100)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.action)
+            .collect()
+    }
+
+    #[test]
+    fn seed_round_trips_with_recorded_kdf_params() {
+        let keypair = Keypair::new();
+        let stored = sealed(&keypair, "hunter2");
+
+        assert_eq!(stored.kdf.algorithm, KDF_ALGORITHM);
+        assert_eq!(stored.kdf.memory_kib, KDF_MEMORY_KIB);
+        assert_eq!(stored.kdf.iterations, KDF_ITERATIONS);
+        assert_eq!(stored.kdf.parallelism, KDF_PARALLELISM);
+        assert_eq!(hex::decode(&stored.kdf.salt).unwrap().len(), 16);
+
+        // The parameters are read back from the key file, not the constants.
+        let reloaded: StoredKey =
+            serde_json::from_str(&serde_json::to_string(&stored).unwrap()).unwrap();
+        let unlocked = decrypt_seed(&reloaded, "hunter2").unwrap();
+        assert_eq!(unlocked.pubkey(), keypair.pubkey());
+
+        let mut tampered = reloaded;
+        tampered.kdf.iterations += 1;
+        assert_eq!(decrypt_seed(&tampered, "hunter2").err().unwrap(), "Invalid password");
+
+        tampered.kdf.algorithm = "scrypt".to_string();
+        assert_eq!(
+            decrypt_seed(&tampered, "hunter2").err().unwrap(),
+            "Unsupported key derivation: scrypt"
+        );
+    }
+
+    #[test]
+    fn wrong_password_is_rejected() {
+        let stored = sealed(&Keypair::new(), "hunter2");
+        assert_eq!(decrypt_seed(&stored, "hunter3").err().unwrap(), "Invalid password");
+    }
+
+    #[test]
+    fn ciphertext_is_bound_to_its_pubkey() {
+        let mut stored = sealed(&Keypair::new(), "hunter2");
+        let other = sealed(&Keypair::new(), "hunter2");
+
+        // A seed moved under another key's public key fails authentication.
+        stored.pubkey = other.pubkey.clone();
+        assert_eq!(decrypt_seed(&stored, "hunter2").err().unwrap(), "Invalid password");
+    }
+
+    #[tokio::test]
+    async fn rotate_replaces_key_and_keeps_label() {
+        let temp = TempKeystore::open(None);
+        let keystore = &temp.keystore;
+        let original = keystore
+            .create(Keypair::new(), Some("hot".to_string()), Some("pw".to_string()), false, None)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            keystore
+                .rotate("hot", Some("wrong".to_string()), None, None)
+                .await
+                .err()
+                .unwrap(),
+            "Invalid password"
+        );
+
+        let (retired, replacement) = keystore
+            .rotate("hot", Some("pw".to_string()), Some("pw2".to_string()), Some("alice"))
+            .await
+            .unwrap();
+
+        assert_eq!(retired.id, original.id);
+        assert!(retired.disabled);
+        assert_eq!(retired.label, None);
+        assert_eq!(retired.rotated_to.as_deref(), Some(replacement.id.as_str()));
+        assert_eq!(replacement.label.as_deref(), Some("hot"));
+        assert_eq!(replacement.rotated_from.as_deref(), Some(original.id.as_str()));
+        assert_ne!(replacement.pubkey, original.pubkey);
+
+        let unlocked = keystore
+            .unlock("hot", Some("pw2".to_string()), None, &message())
+            .await
+            .unwrap();
+        assert_eq!(unlocked.pubkey().to_string(), replacement.pubkey);
+
+        assert_eq!(
+            keystore
+                .unlock(&original.id, Some("pw".to_string()), None, &message())
+                .await
+                .err()
+                .unwrap(),
+            "Key is disabled"
+        );
+        assert_eq!(
+            keystore
+                .rotate(&original.id, Some("pw".to_string()), None, None)
+                .await
+                .err()
+                .unwrap(),
+            "Key was already rotated"
+        );
+
+        assert_eq!(
+            audit_actions(keystore),
+            ["create", "unlock_failed", "rotate", "create", "use", "use_denied"]
+        );
+        let actors: Vec<Option<String>> = keystore
+            .audit_trail(None, 100)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.actor)
+            .collect();
+        assert_eq!(actors[2].as_deref(), Some("alice"));
+        assert_eq!(actors[3].as_deref(), Some("alice"));
+    }
+
+    #[tokio::test]
+    async fn delete_requires_password_or_principal() {
+        let temp = TempKeystore::open(Some("master"));
+        let keystore = &temp.keystore;
+        let protected = keystore
+            .create(Keypair::new(), Some("cold".to_string()), Some("pw".to_string()), false, None)
+            .await
+            .unwrap();
+        let master = keystore
+            .create(Keypair::new(), Some("hot".to_string()), None, false, None)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            keystore.delete("cold", None, Some("alice")).await.err().unwrap(),
+            "Missing required fields: password"
+        );
+        assert_eq!(
+            keystore
+                .delete("cold", Some("nope".to_string()), None)
+                .await
+                .err()
+                .unwrap(),
+            "Invalid password"
+        );
+        assert_eq!(
+            keystore.delete("hot", None, None).await.err().unwrap(),
+            "API key required"
+        );
+        assert_eq!(keystore.key_count().await, 2);
+
+        let deleted = keystore
+            .delete("cold", Some("pw".to_string()), None)
+            .await
+            .unwrap();
+        assert_eq!(deleted.id, protected.id);
+        let deleted = keystore.delete("hot", None, Some("alice")).await.unwrap();
+        assert_eq!(deleted.id, master.id);
+        assert_eq!(keystore.key_count().await, 0);
+        assert!(!keystore.dir().join(format!("{}.json", master.id)).exists());
+
+        let last = keystore.audit_trail(None, 1).unwrap().remove(0);
+        assert_eq!(last.action, "delete");
+        assert_eq!(last.key_id, master.id);
+        assert_eq!(last.actor.as_deref(), Some("alice"));
+    }
+
+    #[tokio::test]
+    async fn update_requires_password_or_principal() {
+        let temp = TempKeystore::open(Some("master"));
+        let keystore = &temp.keystore;
+        keystore
+            .create(Keypair::new(), Some("cold".to_string()), Some("pw".to_string()), false, None)
+            .await
+            .unwrap();
+        keystore
+            .create(Keypair::new(), Some("hot".to_string()), None, false, None)
+            .await
+            .unwrap();
+
+        let relabel = |label: &str| Some(label.to_string());
+        assert_eq!(
+            keystore
+                .update("cold", relabel("hot2"), None, None, Some("alice"))
+                .await
+                .err()
+                .unwrap(),
+            "Missing required fields: password"
+        );
+        assert_eq!(
+            keystore
+                .update("hot", relabel("cold2"), Some(true), None, None)
+                .await
+                .err()
+                .unwrap(),
+            "API key required"
+        );
+        assert!(keystore.resolve("hot2").await.is_none());
+        assert!(!keystore.resolve("hot").await.unwrap().disabled);
+
+        let updated = keystore
+            .update("cold", relabel("archive"), None, Some("pw".to_string()), None)
+            .await
+            .unwrap();
+        assert_eq!(updated.label.as_deref(), Some("archive"));
+        let updated = keystore
+            .update("hot", None, Some(true), None, Some("alice"))
+            .await
+            .unwrap();
+        assert!(updated.disabled);
+    }
+
+    #[tokio::test]
+    async fn rotating_a_master_key_requires_a_principal() {
+        let temp = TempKeystore::open(Some("master"));
+        let keystore = &temp.keystore;
+        let original = keystore
+            .create(Keypair::new(), Some("hot".to_string()), None, false, None)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            keystore.rotate("hot", None, None, None).await.err().unwrap(),
+            "API key required"
+        );
+        let unchanged = keystore.resolve("hot").await.unwrap();
+        assert_eq!(unchanged.id, original.id);
+        assert!(!unchanged.disabled);
+        assert_eq!(keystore.key_count().await, 1);
+
+        let (retired, replacement) = keystore
+            .rotate("hot", None, None, Some("alice"))
+            .await
+            .unwrap();
+        assert_eq!(retired.id, original.id);
+        assert_eq!(replacement.protection, Protection::Master);
+        assert_eq!(keystore.resolve("hot").await.unwrap().id, replacement.id);
+    }
+
+    #[tokio::test]
+    async fn signer_takes_exactly_one_of_secret_or_key_id() {
+        let temp = TempKeystore::open(Some("master"));
+        let keystore = &temp.keystore;
+        let keypair = Keypair::new();
+        let pubkey = keypair.pubkey();
+        let secret = keypair.to_base58_string();
+        let stored = keystore.create(keypair, None, None, true, None).await.unwrap();
+        assert_eq!(stored.protection, Protection::Master);
+
+        let inline = keystore
+            .signer(Some(secret.clone()), None, None, None, None, message())
+            .await
+            .unwrap();
+        assert_eq!(inline.pubkey(), pubkey);
+
+        let anonymous = keystore
+            .signer(None, None, Some(stored.id.clone()), None, None, message())
+            .await;
+        assert_eq!(anonymous.err().unwrap(), "API key required");
+
+        let unlocked = keystore
+            .signer(None, None, Some(stored.id.clone()), None, Some("alice"), message())
+            .await
+            .unwrap();
+        assert_eq!(unlocked.pubkey(), pubkey);
+
+        let last = keystore.audit_trail(None, 1).unwrap().remove(0);
+        assert_eq!(last.action, "use");
+        assert_eq!(last.actor.as_deref(), Some("alice"));
+
+        let both = keystore
+            .signer(Some(secret), None, Some(stored.id.clone()), None, None, message())
+            .await;
+        assert_eq!(both.err().unwrap(), "Provide either secret or keyId, not both");
+
+        let neither = keystore
+            .signer(Some(" ".to_string()), None, Some("".to_string()), None, None, message())
+            .await;
+        assert_eq!(neither.err().unwrap(), "Missing required fields");
+
+        let unknown = keystore
+            .signer(None, None, Some("missing".to_string()), None, None, message())
+            .await;
+        assert_eq!(unknown.err().unwrap(), "Key not found");
+    }
+
+    #[tokio::test]
+    async fn signer_requires_the_key_password() {
+        let temp = TempKeystore::open(Some("master"));
+        let keystore = &temp.keystore;
+        let stored = keystore
+            .create(Keypair::new(), None, Some("pw".to_string()), false, None)
+            .await
+            .unwrap();
+
+        let missing = keystore
+            .signer(None, None, Some(stored.id.clone()), None, None, message())
+            .await;
+        assert_eq!(missing.err().unwrap(), "Missing required fields: password");
+
+        let wrong = keystore
+            .signer(
+                None,
+                None,
+                Some(stored.id.clone()),
+                Some("nope".to_string()),
+                None,
+                message(),
+            )
+            .await;
+        assert_eq!(wrong.err().unwrap(), "Invalid password");
+
+        let unlocked = keystore
+            .signer(
+                None,
+                None,
+                Some(stored.id.clone()),
+                Some("pw".to_string()),
+                None,
+                message(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(unlocked.pubkey().to_string(), stored.pubkey);
+    }
+
+    #[tokio::test]
+    async fn policy_keys_do_not_sign_transaction_messages_as_raw() {
+        let temp = TempKeystore::open(Some("master"));
+        let keystore = &temp.keystore;
+        let keypair = Keypair::new();
+        let pubkey = keypair.pubkey();
+        let stored = keystore.create(keypair, None, None, true, None).await.unwrap();
+        let policy: SigningPolicy =
+            serde_json::from_value(serde_json::json!({ "maxLamportsPerTransfer": 1000 })).unwrap();
+        keystore.set_policy(&stored.id, Some(policy), Some("alice")).await.unwrap();
+
+        // A transfer far above the limit, handed over as raw message bytes.
+        let transfer =
+            solana_sdk::system_instruction::transfer(&pubkey, &Pubkey::new_unique(), 1_000_000);
+        let legacy = solana_sdk::message::Message::new(std::slice::from_ref(&transfer), Some(&pubkey));
+        let v0 = solana_sdk::message::v0::Message::try_compile(
+            &pubkey,
+            &[transfer],
+            &[],
+            solana_sdk::hash::Hash::default(),
+        )
+        .unwrap();
+
+        for bytes in [legacy.serialize(), VersionedMessage::V0(v0).serialize()] {
+            let rejected = keystore
+                .unlock(&stored.id, None, Some("alice"), &SigningRequest::Message(&bytes))
+                .await;
+            assert_eq!(
+                rejected.err().unwrap(),
+                "Policy violation: message decodes as a transaction; use the transaction signer"
+            );
+        }
+        assert_eq!(audit_actions(keystore).last().unwrap(), "policy_rejected");
+
+        let unlocked = keystore
+            .unlock(&stored.id, None, Some("alice"), &message())
+            .await
+            .unwrap();
+        assert_eq!(unlocked.pubkey(), pubkey);
+    }
+}
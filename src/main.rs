@@ -1,32 +1,175 @@
-use axum::{routing::post, Router};
+use axum::{
+    routing::{get, post, put},
+    Router,
+};
 use tower_http::cors::CorsLayer;
 
+mod approvals;
+mod auth;
+mod blockhash;
+mod cluster;
+mod decoder;
+mod fees;
+mod idl;
+mod keystore;
+mod policy;
+mod pubsub;
 mod routes;
+mod rpc;
+mod siws;
+mod state;
+mod tracker;
 mod types;
 mod utils;
+mod vanity;
+mod verify;
+mod watch;
 
 #[tokio::main]
 async fn main() {
     println!("🚀 Starting Solana HTTP server...");
 
+    let clusters = cluster::ClusterRegistry::load().expect("Failed to load cluster configuration");
+    println!("🌐 Default cluster: {}", clusters.default_name());
+    let keystore = keystore::Keystore::load().expect("Failed to load keystore");
+    println!(
+        "🔐 Keystore: {} ({} keys)",
+        keystore.dir().display(),
+        keystore.key_count().await
+    );
+    let api_keys = auth::ApiKeys::load().expect("Failed to load API keys");
+    println!("🔑 API keys: {} principals", api_keys.principal_count());
+    let siws = siws::SiwsAuth::load().expect("Failed to load SIWS configuration");
+    println!("🪪 SIWS domains: {}", siws.domains().join(", "));
+    let idls = idl::IdlRegistry::load();
+    println!("📜 Anchor IDLs: {} loaded", idls.len());
+    let state = state::AppState::new(clusters, keystore, api_keys, siws, idls);
+    state.spawn_background_tasks();
+
     let app = Router::new()
         .route("/keypair", post(routes::keypair::handle_keypair_generation))
+        .route("/keypair/recover", post(routes::keypair::handle_keypair_recover))
+        .route("/keypair/derive", post(routes::keypair::handle_keypair_derive))
+        .route("/keypair/convert", post(routes::keypair::handle_keypair_convert))
+        .route("/keypair/vanity", post(routes::keypair::handle_keypair_vanity))
+        .route(
+            "/keypair/vanity/:id",
+            get(routes::keypair::handle_vanity_job_get).delete(routes::keypair::handle_vanity_job_cancel),
+        )
+        .route("/keys", post(routes::keys::handle_key_create).get(routes::keys::handle_key_list))
+        .route("/keys/audit", get(routes::keys::handle_key_audit))
+        .route(
+            "/keys/:id",
+            get(routes::keys::handle_key_get)
+                .put(routes::keys::handle_key_update)
+                .delete(routes::keys::handle_key_delete),
+        )
+        .route("/keys/:id/rotate", post(routes::keys::handle_key_rotate))
+        .route(
+            "/keys/:id/policy",
+            put(routes::keys::handle_key_policy_set)
+                .delete(routes::keys::handle_key_policy_clear),
+        )
+        .route("/auth/siws/challenge", post(routes::auth::handle_siws_challenge))
+        .route("/auth/siws/verify", post(routes::auth::handle_siws_verify))
+        .route("/auth/siws/session", get(routes::auth::handle_siws_session))
+        .route("/approvals", get(routes::approvals::handle_approval_list))
+        .route("/approvals/:id", get(routes::approvals::handle_approval_get))
+        .route("/approvals/:id/approve", post(routes::approvals::handle_approval_approve))
+        .route("/approvals/:id/reject", post(routes::approvals::handle_approval_reject))
+        .route("/approvals/:id/execute", post(routes::approvals::handle_approval_execute))
         .route("/token/create", post(routes::token::handle_token_creation))
         .route("/token/mint", post(routes::token::handle_token_minting))
+        .route("/token/mint/:address", get(routes::token::handle_mint_state))
+        .route("/token/account/:address", get(routes::token::handle_token_account_state))
         .route("/message/sign", post(routes::message::handle_message_signing))
         .route("/message/verify", post(routes::message::handle_message_verification))
+        .route("/message/verify/batch", post(routes::message::handle_message_verify_batch))
         .route("/send/sol", post(routes::send::handle_sol_transfer))
         .route("/send/token", post(routes::send::handle_token_transfer))
-        .layer(CorsLayer::permissive());
+        .route("/instruction/anchor", post(routes::instruction::handle_anchor_instruction))
+        .route("/instruction/raw", post(routes::instruction::handle_raw_instruction))
+        .route("/transaction/decode", post(routes::transaction::handle_transaction_decode))
+        .route("/transaction/:signature", get(routes::transaction::handle_transaction_details))
+        .route("/account/:pubkey", get(routes::account::handle_account_info))
+        .route("/account/:pubkey/balance", get(routes::account::handle_account_balance))
+        .route("/account/:pubkey/tokens", get(routes::account::handle_account_tokens))
+        .route("/account/:pubkey/signatures", get(routes::account::handle_account_signatures))
+        .route("/airdrop", post(routes::airdrop::handle_airdrop))
+        .route("/clusters", get(routes::cluster::handle_cluster_list))
+        .route("/blockhash", get(routes::cluster::handle_latest_blockhash))
+        .route("/transaction/build", post(routes::transaction::handle_transaction_build))
+        .route("/transaction/fee", post(routes::transaction::handle_transaction_fee))
+        .route("/transaction/sign", post(routes::transaction::handle_transaction_sign))
+        .route("/transaction/send", post(routes::transaction::handle_transaction_send))
+        .route("/transaction/:signature/status", get(routes::transaction::handle_transaction_status))
+        .route("/ws", get(routes::ws::handle_ws))
+        .route("/watch", post(routes::watch::handle_watch_create).get(routes::watch::handle_watch_list))
+        .route(
+            "/watch/:id",
+            get(routes::watch::handle_watch_get)
+                .put(routes::watch::handle_watch_update)
+                .delete(routes::watch::handle_watch_delete),
+        )
+        .layer(CorsLayer::permissive())
+        .with_state(state);
 
     println!("📍 Available endpoints:");
     println!("  POST /keypair");
+    println!("  POST /keypair/recover");
+    println!("  POST /keypair/derive");
+    println!("  POST /keypair/convert");
+    println!("  POST /keypair/vanity");
+    println!("  GET  /keypair/vanity/:id");
+    println!("  DELETE /keypair/vanity/:id");
+    println!("  POST /keys");
+    println!("  GET  /keys");
+    println!("  GET  /keys/audit");
+    println!("  GET  /keys/:id");
+    println!("  PUT  /keys/:id");
+    println!("  DELETE /keys/:id");
+    println!("  POST /keys/:id/rotate");
+    println!("  PUT  /keys/:id/policy");
+    println!("  DELETE /keys/:id/policy");
+    println!("  POST /auth/siws/challenge");
+    println!("  POST /auth/siws/verify");
+    println!("  GET  /auth/siws/session");
+    println!("  GET  /approvals");
+    println!("  GET  /approvals/:id");
+    println!("  POST /approvals/:id/approve");
+    println!("  POST /approvals/:id/reject");
+    println!("  POST /approvals/:id/execute");
     println!("  POST /token/create");
     println!("  POST /token/mint");
+    println!("  GET  /token/mint/:address");
+    println!("  GET  /token/account/:address");
     println!("  POST /message/sign");
     println!("  POST /message/verify");
+    println!("  POST /message/verify/batch");
     println!("  POST /send/sol");
     println!("  POST /send/token");
+    println!("  POST /instruction/anchor");
+    println!("  POST /instruction/raw");
+    println!("  POST /transaction/decode");
+    println!("  GET  /transaction/:signature");
+    println!("  GET  /account/:pubkey");
+    println!("  GET  /account/:pubkey/balance");
+    println!("  GET  /account/:pubkey/tokens");
+    println!("  GET  /account/:pubkey/signatures");
+    println!("  POST /airdrop");
+    println!("  GET  /clusters");
+    println!("  GET  /blockhash");
+    println!("  POST /transaction/build");
+    println!("  POST /transaction/fee");
+    println!("  POST /transaction/sign");
+    println!("  POST /transaction/send");
+    println!("  GET  /transaction/:signature/status");
+    println!("  GET  /ws");
+    println!("  POST /watch");
+    println!("  GET  /watch");
+    println!("  GET  /watch/:id");
+    println!("  PUT  /watch/:id");
+    println!("  DELETE /watch/:id");
 
     let listener = tokio::net::TcpListener::bind("127.0.0.1:3000")
         .await
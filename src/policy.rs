@@ -0,0 +1,571 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use solana_sdk::{message::VersionedMessage, pubkey::Pubkey};
+use std::{collections::HashMap, str::FromStr};
+
+use crate::{decoder::decode_message_instructions, types::response::DecodedInstruction};
+
+const SECONDS_PER_DAY: u64 = 86_400;
+const WEEKDAYS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+const DEFAULT_APPROVAL_QUORUM: u32 = 2;
+
+pub enum SigningRequest<'a> {
+    // The exact bytes that will be signed.
+    Message(&'a [u8]),
+    Transaction(&'a VersionedMessage),
+    // A transaction whose approval quorum has already been met.
+    ApprovedTransaction(&'a VersionedMessage),
+}
+
+// Signing is allowed while the current UTC time falls inside the window. A
+// window whose end is before its start runs past midnight.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TimeWindow {
+    pub days: Option<Vec<String>>,
+    pub start: String,
+    pub end: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct SigningPolicy {
+    #[serde(rename = "allowedPrograms", skip_serializing_if = "Option::is_none")]
+    pub allowed_programs: Option<Vec<String>>,
+    #[serde(rename = "maxLamportsPerTransfer", skip_serializing_if = "Option::is_none")]
+    pub max_lamports_per_transfer: Option<u64>,
+    #[serde(rename = "maxTokenAmount", skip_serializing_if = "Option::is_none")]
+    pub max_token_amount: Option<HashMap<String, u64>>,
+    #[serde(rename = "allowedDestinations", skip_serializing_if = "Option::is_none")]
+    pub allowed_destinations: Option<Vec<String>>,
+    #[serde(rename = "dailyLamportLimit", skip_serializing_if = "Option::is_none")]
+    pub daily_lamport_limit: Option<u64>,
+    #[serde(rename = "dailyTokenLimits", skip_serializing_if = "Option::is_none")]
+    pub daily_token_limits: Option<HashMap<String, u64>>,
+    #[serde(rename = "timeWindows", skip_serializing_if = "Option::is_none")]
+    pub time_windows: Option<Vec<TimeWindow>>,
+    #[serde(rename = "approvalThresholdLamports", skip_serializing_if = "Option::is_none")]
+    pub approval_threshold_lamports: Option<u64>,
+    #[serde(rename = "approvalTokenThresholds", skip_serializing_if = "Option::is_none")]
+    pub approval_token_thresholds: Option<HashMap<String, u64>>,
+    #[serde(rename = "approvalQuorum", skip_serializing_if = "Option::is_none")]
+    pub approval_quorum: Option<u32>,
+}
+
+// Amounts signed for on the given UTC day (days since the unix epoch).
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct DailySpend {
+    pub day: u64,
+    pub lamports: u64,
+    pub tokens: HashMap<String, u64>,
+}
+
+#[derive(Default, Debug)]
+pub struct Spend {
+    pub lamports: u64,
+    pub tokens: HashMap<String, u64>,
+}
+
+fn parse_minutes(value: &str) -> Result<u32, String> {
+    let (hours, minutes) = value
+        .trim()
+        .split_once(':')
+        .ok_or_else(|| format!("Invalid time: {} (expected HH:MM)", value))?;
+
+    match (hours.parse::<u32>(), minutes.parse::<u32>()) {
+        (Ok(h), Ok(m)) if h < 24 && m < 60 => Ok(h * 60 + m),
+        _ => Err(format!("Invalid time: {} (expected HH:MM)", value)),
+    }
+}
+
+fn validate_address(value: &str, field: &str) -> Result<(), String> {
+    Pubkey::from_str(value.trim())
+        .map(|_| ())
+        .map_err(|_| format!("Invalid {}: {}", field, value))
+}
+
+// A limit is loosened when it is dropped or raised.
+fn looser_limit(current: Option<u64>, next: Option<u64>) -> bool {
+    current.is_some_and(|current| next.is_none_or(|next| next > current))
+}
+
+fn looser_limits(current: &Option<HashMap<String, u64>>, next: &Option<HashMap<String, u64>>) -> bool {
+    current.as_ref().is_some_and(|current| {
+        current.iter().any(|(mint, limit)| {
+            looser_limit(Some(*limit), next.as_ref().and_then(|n| n.get(mint)).copied())
+        })
+    })
+}
+
+// An allow-list is loosened when it is dropped or gains an entry.
+fn looser_list(current: &Option<Vec<String>>, next: &Option<Vec<String>>) -> bool {
+    current.as_ref().is_some_and(|current| {
+        next.as_ref().is_none_or(|next| {
+            next.iter()
+                .any(|entry| !current.iter().any(|c| c.trim() == entry.trim()))
+        })
+    })
+}
+
+// Unresolved accounts decode as empty strings, which never match a rule.
+// A raw message that decodes as a transaction message would make a valid
+// transaction signature, so keys under a policy only sign those through the
+// transaction routes, where the policy is evaluated.
+pub fn check_message(message: &[u8]) -> Result<(), String> {
+    if bincode::deserialize::<VersionedMessage>(message).is_ok() {
+        return Err("message decodes as a transaction; use the transaction signer".to_string());
+    }
+    Ok(())
+}
+
+fn info_str<'a>(info: &'a Value, field: &str) -> Option<&'a str> {
+    info.get(field).and_then(Value::as_str).filter(|v| !v.is_empty())
+}
+
+fn info_u64(info: &Value, field: &str) -> Option<u64> {
+    info.get(field).and_then(Value::as_u64)
+}
+
+impl TimeWindow {
+    fn contains(&self, now: u64) -> Result<bool, String> {
+        let start = parse_minutes(&self.start)?;
+        let end = parse_minutes(&self.end)?;
+        let minute = ((now % SECONDS_PER_DAY) / 60) as u32;
+        // 1970-01-01 was a Thursday.
+        let weekday = WEEKDAYS[((now / SECONDS_PER_DAY + 3) % 7) as usize];
+
+        let day_matches = self
+            .days
+            .as_ref()
+            .is_none_or(|days| days.iter().any(|d| d.trim().eq_ignore_ascii_case(weekday)));
+
+        let time_matches = if start <= end {
+            minute >= start && minute < end
+        } else {
+            minute >= start || minute < end
+        };
+
+        Ok(day_matches && time_matches)
+    }
+}
+
+impl SigningPolicy {
+    pub fn validate(&self) -> Result<(), String> {
+        for program in self.allowed_programs.iter().flatten() {
+            validate_address(program, "allowedPrograms entry")?;
+        }
+        for destination in self.allowed_destinations.iter().flatten() {
+            validate_address(destination, "allowedDestinations entry")?;
+        }
+        for mint in self.max_token_amount.iter().flat_map(|m| m.keys()) {
+            validate_address(mint, "maxTokenAmount mint")?;
+        }
+        for mint in self.daily_token_limits.iter().flat_map(|m| m.keys()) {
+            validate_address(mint, "dailyTokenLimits mint")?;
+        }
+        for mint in self.approval_token_thresholds.iter().flat_map(|m| m.keys()) {
+            validate_address(mint, "approvalTokenThresholds mint")?;
+        }
+        if self.approval_quorum == Some(0) {
+            return Err("Invalid approvalQuorum: must be at least 1".to_string());
+        }
+        for window in self.time_windows.iter().flatten() {
+            parse_minutes(&window.start)?;
+            parse_minutes(&window.end)?;
+            for day in window.days.iter().flatten() {
+                if !WEEKDAYS.contains(&day.trim().to_ascii_lowercase().as_str()) {
+                    return Err(format!("Invalid day: {} (expected mon..sun)", day));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn has_spend_rules(&self) -> bool {
+        self.max_lamports_per_transfer.is_some()
+            || self.max_token_amount.is_some()
+            || self.allowed_destinations.is_some()
+            || self.daily_lamport_limit.is_some()
+            || self.daily_token_limits.is_some()
+            || self.approval_threshold_lamports.is_some()
+            || self.approval_token_thresholds.is_some()
+    }
+
+    fn has_token_limits(&self) -> bool {
+        self.max_token_amount.is_some()
+            || self.daily_token_limits.is_some()
+            || self.approval_token_thresholds.is_some()
+    }
+
+    // Whether replacing this policy with `next` (or clearing it) could let
+    // through anything this one rejects or parks for approval. Changes that
+    // are not clearly at least as strict count as loosening; time windows
+    // are only compared for equality.
+    pub fn is_loosened_by(&self, next: Option<&SigningPolicy>) -> bool {
+        let Some(next) = next else {
+            return true;
+        };
+
+        let windows_changed = self.time_windows.is_some()
+            && serde_json::to_value(&self.time_windows).ok()
+                != serde_json::to_value(&next.time_windows).ok();
+
+        looser_list(&self.allowed_programs, &next.allowed_programs)
+            || looser_list(&self.allowed_destinations, &next.allowed_destinations)
+            || looser_limit(self.max_lamports_per_transfer, next.max_lamports_per_transfer)
+            || looser_limit(self.daily_lamport_limit, next.daily_lamport_limit)
+            || looser_limit(self.approval_threshold_lamports, next.approval_threshold_lamports)
+            || looser_limits(&self.max_token_amount, &next.max_token_amount)
+            || looser_limits(&self.daily_token_limits, &next.daily_token_limits)
+            || looser_limits(&self.approval_token_thresholds, &next.approval_token_thresholds)
+            || windows_changed
+            || next.quorum() < self.quorum()
+    }
+
+    pub fn quorum(&self) -> u32 {
+        self.approval_quorum.unwrap_or(DEFAULT_APPROVAL_QUORUM)
+    }
+
+    pub fn requires_approval(&self, spend: &Spend) -> bool {
+        let lamports_over = self
+            .approval_threshold_lamports
+            .is_some_and(|threshold| spend.lamports > threshold);
+
+        let tokens_over = self.approval_token_thresholds.as_ref().is_some_and(|thresholds| {
+            spend
+                .tokens
+                .iter()
+                .any(|(mint, amount)| thresholds.get(mint).is_some_and(|t| amount > t))
+        });
+
+        lamports_over || tokens_over
+    }
+
+    pub fn check_time(&self, now: u64) -> Result<(), String> {
+        let Some(windows) = &self.time_windows else {
+            return Ok(());
+        };
+
+        for window in windows {
+            if window.contains(now)? {
+                return Ok(());
+            }
+        }
+        Err("signing is not allowed at this time".to_string())
+    }
+
+    fn check_destination(&self, index: usize, destination: Option<&str>) -> Result<(), String> {
+        let Some(allowed) = &self.allowed_destinations else {
+            return Ok(());
+        };
+
+        match destination {
+            Some(dest) if allowed.iter().any(|a| a.trim() == dest) => Ok(()),
+            Some(dest) => Err(format!(
+                "instruction {}: destination {} is not allowed",
+                index, dest
+            )),
+            None => Err(format!("instruction {}: destination cannot be determined", index)),
+        }
+    }
+
+    fn add_lamports(&self, spend: &mut Spend, index: usize, lamports: u64) -> Result<(), String> {
+        if let Some(max) = self.max_lamports_per_transfer
+            && lamports > max
+        {
+            return Err(format!(
+                "instruction {}: transfer of {} lamports exceeds the limit of {}",
+                index, lamports, max
+            ));
+        }
+        spend.lamports = spend.lamports.saturating_add(lamports);
+        Ok(())
+    }
+
+    fn add_tokens(
+        &self,
+        spend: &mut Spend,
+        index: usize,
+        mint: &str,
+        amount: u64,
+    ) -> Result<(), String> {
+        if let Some(max) = self.max_token_amount.as_ref().and_then(|m| m.get(mint))
+            && amount > *max
+        {
+            return Err(format!(
+                "instruction {}: transfer of {} tokens of mint {} exceeds the limit of {}",
+                index, amount, mint, max
+            ));
+        }
+        let total = spend.tokens.entry(mint.to_string()).or_default();
+        *total = total.saturating_add(amount);
+        Ok(())
+    }
+
+    // Decodes every instruction and returns what the signer would spend. When
+    // spend rules are set, any instruction the signer authorizes that cannot
+    // be accounted for (unknown programs, approvals, authority changes) is
+    // rejected rather than waved through.
+    pub fn evaluate(&self, signer: &Pubkey, message: &VersionedMessage) -> Result<Spend, String> {
+        // Accounts loaded from lookup tables are not known without fetching
+        // the tables, so destinations and mints could not be checked.
+        if (self.allowed_programs.is_some() || self.has_spend_rules())
+            && message
+                .address_table_lookups()
+                .is_some_and(|lookups| !lookups.is_empty())
+        {
+            return Err("transactions using address lookup tables cannot be checked".to_string());
+        }
+
+        let signer = signer.to_string();
+        let instructions = decode_message_instructions(message, None);
+        let mut spend = Spend::default();
+
+        for (index, ix) in instructions.iter().enumerate() {
+            if let Some(allowed) = &self.allowed_programs
+                && !allowed.iter().any(|p| p.trim() == ix.program_id)
+            {
+                return Err(format!(
+                    "instruction {}: program {} is not allowed",
+                    index, ix.program_id
+                ));
+            }
+
+            let signed_by_key = ix.accounts.iter().any(|a| a.is_signer && a.pubkey == signer);
+            if !self.has_spend_rules() || !signed_by_key {
+                continue;
+            }
+
+            self.evaluate_instruction(index, ix, &signer, &mut spend)?;
+        }
+
+        Ok(spend)
+    }
+
+    fn evaluate_instruction(
+        &self,
+        index: usize,
+        ix: &DecodedInstruction,
+        signer: &str,
+        spend: &mut Spend,
+    ) -> Result<(), String> {
+        let unsupported = || {
+            Err(format!(
+                "instruction {}: {} cannot be checked against spend limits",
+                index,
+                match (&ix.program, &ix.instruction_type) {
+                    (Some(program), Some(ty)) => format!("{} {}", program, ty),
+                    _ => format!("program {}", ix.program_id),
+                }
+            ))
+        };
+
+        let (Some(program), Some(ty), Some(info)) = (&ix.program, &ix.instruction_type, &ix.info)
+        else {
+            return unsupported();
+        };
+
+        if ix.accounts.iter().any(|a| a.pubkey.is_empty()) {
+            return unsupported();
+        }
+
+        match (program.as_str(), ty.as_str()) {
+            ("compute-budget", _) | ("spl-memo", _) | ("spl-associated-token-account", _) => Ok(()),
+            (
+                "system",
+                "transfer" | "transferWithSeed" | "createAccount" | "createAccountWithSeed",
+            ) => {
+                if info_str(info, "from") != Some(signer) {
+                    return Ok(());
+                }
+                let destination = info_str(info, "to").or_else(|| info_str(info, "newAccount"));
+                self.check_destination(index, destination)?;
+                self.add_lamports(spend, index, info_u64(info, "lamports").unwrap_or_default())
+            }
+            ("system", "withdrawFromNonce") => {
+                if info_str(info, "nonceAuthority") != Some(signer) {
+                    return Ok(());
+                }
+                self.check_destination(index, info_str(info, "to"))?;
+                self.add_lamports(spend, index, info_u64(info, "lamports").unwrap_or_default())
+            }
+            (
+                "system",
+                "advanceNonce" | "initializeNonce" | "authorizeNonce" | "upgradeNonce",
+            ) => Ok(()),
+            ("spl-token", "transfer") => {
+                if self.has_token_limits() {
+                    return Err(format!(
+                        "instruction {}: token limits require transferChecked so the mint is known",
+                        index
+                    ));
+                }
+                self.check_destination(index, info_str(info, "destination"))
+            }
+            ("spl-token", "transferChecked") => {
+                self.check_destination(index, info_str(info, "destination"))?;
+                let mint = info_str(info, "mint").unwrap_or_default();
+                self.add_tokens(spend, index, mint, info_u64(info, "amount").unwrap_or_default())
+            }
+            ("spl-token", "closeAccount") => {
+                self.check_destination(index, info_str(info, "destination"))
+            }
+            (
+                "spl-token",
+                "initializeMint" | "initializeMint2" | "initializeAccount" | "initializeAccount2"
+                | "initializeAccount3" | "initializeMultisig" | "initializeMultisig2" | "revoke"
+                | "mintTo" | "mintToChecked" | "burn" | "burnChecked" | "freezeAccount"
+                | "thawAccount" | "syncNative" | "getAccountDataSize" | "initializeImmutableOwner"
+                | "amountToUiAmount" | "uiAmountToAmount",
+            ) => Ok(()),
+            _ => unsupported(),
+        }
+    }
+
+    // Adds the spend to today's running totals, failing without changes if a
+    // daily limit would be exceeded.
+    pub fn apply_daily(
+        &self,
+        current: Option<&DailySpend>,
+        spend: &Spend,
+        now: u64,
+    ) -> Result<DailySpend, String> {
+        let today = now / SECONDS_PER_DAY;
+        let mut totals = match current {
+            Some(current) if current.day == today => current.clone(),
+            _ => DailySpend {
+                day: today,
+                ..DailySpend::default()
+            },
+        };
+
+        totals.lamports = totals.lamports.saturating_add(spend.lamports);
+        if let Some(limit) = self.daily_lamport_limit
+            && totals.lamports > limit
+        {
+            return Err(format!(
+                "daily limit of {} lamports would be exceeded ({} already signed today)",
+                limit,
+                totals.lamports - spend.lamports
+            ));
+        }
+
+        for (mint, amount) in &spend.tokens {
+            let total = totals.tokens.entry(mint.clone()).or_default();
+            *total = total.saturating_add(*amount);
+
+            if let Some(limit) = self.daily_token_limits.as_ref().and_then(|l| l.get(mint))
+                && *total > *limit
+            {
+                return Err(format!(
+                    "daily limit of {} tokens of mint {} would be exceeded ({} already signed today)",
+                    limit,
+                    mint,
+                    *total - amount
+                ));
+            }
+        }
+
+        Ok(totals)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{
+        hash::Hash,
+        message::{v0, v0::MessageAddressTableLookup},
+    };
+
+    const MINT: &str = "So11111111111111111111111111111111111111112";
+
+    fn policy(json: Value) -> SigningPolicy {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn clearing_or_raising_limits_loosens() {
+        let current = policy(serde_json::json!({
+            "maxLamportsPerTransfer": 1000,
+            "approvalThresholdLamports": 500,
+            "maxTokenAmount": { MINT: 10 },
+            "allowedPrograms": ["11111111111111111111111111111111"],
+        }));
+
+        assert!(current.is_loosened_by(None));
+        assert!(current.is_loosened_by(Some(&SigningPolicy::default())));
+
+        let mut raised = current.clone();
+        raised.max_lamports_per_transfer = Some(1001);
+        assert!(current.is_loosened_by(Some(&raised)));
+
+        let mut more_programs = current.clone();
+        more_programs
+            .allowed_programs
+            .as_mut()
+            .unwrap()
+            .push(spl_token::id().to_string());
+        assert!(current.is_loosened_by(Some(&more_programs)));
+
+        let mut token_limit_dropped = current.clone();
+        token_limit_dropped.max_token_amount = Some(HashMap::new());
+        assert!(current.is_loosened_by(Some(&token_limit_dropped)));
+
+        let mut lower_quorum = current.clone();
+        lower_quorum.approval_quorum = Some(1);
+        assert!(current.is_loosened_by(Some(&lower_quorum)));
+    }
+
+    #[test]
+    fn tightening_does_not_loosen() {
+        let current = policy(serde_json::json!({
+            "maxLamportsPerTransfer": 1000,
+            "maxTokenAmount": { MINT: 10 },
+        }));
+
+        assert!(!current.is_loosened_by(Some(&current.clone())));
+
+        let tighter = policy(serde_json::json!({
+            "maxLamportsPerTransfer": 900,
+            "maxTokenAmount": { MINT: 5 },
+            "dailyLamportLimit": 5000,
+            "allowedDestinations": ["11111111111111111111111111111111"],
+            "approvalQuorum": 3,
+        }));
+        assert!(!current.is_loosened_by(Some(&tighter)));
+        assert!(tighter.is_loosened_by(Some(&current)));
+    }
+
+    fn transfer_message(lookups: Vec<MessageAddressTableLookup>) -> (Pubkey, VersionedMessage) {
+        let signer = Pubkey::new_unique();
+        let instruction =
+            solana_sdk::system_instruction::transfer(&signer, &Pubkey::new_unique(), 100);
+        let mut message = v0::Message::try_compile(&signer, &[instruction], &[], Hash::default())
+            .unwrap();
+        message.address_table_lookups = lookups;
+        (signer, VersionedMessage::V0(message))
+    }
+
+    #[test]
+    fn lookup_tables_are_rejected_when_rules_are_set() {
+        let lookup = MessageAddressTableLookup {
+            account_key: Pubkey::new_unique(),
+            writable_indexes: vec![0],
+            readonly_indexes: vec![],
+        };
+        let limited = policy(serde_json::json!({ "maxLamportsPerTransfer": 1000 }));
+
+        let (signer, plain) = transfer_message(Vec::new());
+        assert_eq!(limited.evaluate(&signer, &plain).unwrap().lamports, 100);
+
+        let (signer, with_lookup) = transfer_message(vec![lookup]);
+        assert_eq!(
+            limited.evaluate(&signer, &with_lookup).err().unwrap(),
+            "transactions using address lookup tables cannot be checked"
+        );
+
+        let windows_only = policy(serde_json::json!({
+            "timeWindows": [{ "start": "00:00", "end": "23:59" }],
+        }));
+        assert!(windows_only.evaluate(&signer, &with_lookup).is_ok());
+    }
+}
@@ -0,0 +1,176 @@
+use futures::{stream::BoxStream, StreamExt};
+use serde_json::Value;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    nonblocking::pubsub_client::PubsubClient,
+    rpc_config::{
+        RpcAccountInfoConfig, RpcSignatureSubscribeConfig, RpcTransactionLogsConfig,
+        RpcTransactionLogsFilter,
+    },
+};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::sync::broadcast;
+
+use crate::cluster::Cluster;
+
+const CHANNEL_CAPACITY: usize = 256;
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Topic {
+    Account(Pubkey),
+    Signature(Signature),
+    Logs(Option<Pubkey>),
+}
+
+#[derive(Clone, Debug)]
+pub enum GatewayEvent {
+    Notification(Value),
+    Closed(String),
+}
+
+type Channels = Arc<Mutex<HashMap<(String, Topic), broadcast::Sender<GatewayEvent>>>>;
+
+// One upstream pubsub subscription per (cluster, topic), shared by every
+// websocket client that asks for it. The upstream task unsubscribes once the
+// last receiver is gone.
+#[derive(Default)]
+pub struct PubsubGateway {
+    channels: Channels,
+}
+
+impl PubsubGateway {
+    pub fn subscribe(&self, cluster: &Cluster, topic: Topic) -> broadcast::Receiver<GatewayEvent> {
+        let key = (cluster.name.clone(), topic);
+        let mut channels = self.channels.lock().unwrap();
+
+        if let Some(sender) = channels.get(&key) {
+            return sender.subscribe();
+        }
+
+        let (sender, receiver) = broadcast::channel(CHANNEL_CAPACITY);
+        channels.insert(key.clone(), sender.clone());
+
+        tokio::spawn(run_upstream(
+            self.channels.clone(),
+            key,
+            cluster.ws_url.clone(),
+            cluster.commitment,
+            sender,
+        ));
+
+        receiver
+    }
+}
+
+async fn run_upstream(
+    channels: Channels,
+    key: (String, Topic),
+    ws_url: String,
+    commitment: CommitmentConfig,
+    sender: broadcast::Sender<GatewayEvent>,
+) {
+    let reason = match forward(&key.1, &ws_url, commitment, &sender).await {
+        Ok(()) => "Subscription ended".to_string(),
+        Err(e) => e,
+    };
+
+    {
+        let mut channels = channels.lock().unwrap();
+        if channels
+            .get(&key)
+            .is_some_and(|existing| existing.same_channel(&sender))
+        {
+            channels.remove(&key);
+        }
+    }
+
+    let _ = sender.send(GatewayEvent::Closed(reason));
+}
+
+fn to_values<'a, T: serde::Serialize + Send + 'a>(
+    stream: BoxStream<'a, T>,
+) -> BoxStream<'a, Value> {
+    stream
+        .map(|item| serde_json::to_value(item).unwrap_or(Value::Null))
+        .boxed()
+}
+
+async fn forward(
+    topic: &Topic,
+    ws_url: &str,
+    commitment: CommitmentConfig,
+    sender: &broadcast::Sender<GatewayEvent>,
+) -> Result<(), String> {
+    let client = PubsubClient::new(ws_url)
+        .await
+        .map_err(|e| format!("Pubsub connection failed: {}", e))?;
+
+    let subscribed = match topic {
+        Topic::Account(pubkey) => client
+            .account_subscribe(
+                pubkey,
+                Some(RpcAccountInfoConfig {
+                    encoding: Some(UiAccountEncoding::JsonParsed),
+                    commitment: Some(commitment),
+                    ..RpcAccountInfoConfig::default()
+                }),
+            )
+            .await
+            .map(|(stream, unsubscribe)| (to_values(stream), unsubscribe)),
+        Topic::Signature(signature) => client
+            .signature_subscribe(
+                signature,
+                Some(RpcSignatureSubscribeConfig {
+                    commitment: Some(commitment),
+                    enable_received_notification: Some(false),
+                }),
+            )
+            .await
+            .map(|(stream, unsubscribe)| (to_values(stream), unsubscribe)),
+        Topic::Logs(mentions) => client
+            .logs_subscribe(
+                match mentions {
+                    Some(pubkey) => RpcTransactionLogsFilter::Mentions(vec![pubkey.to_string()]),
+                    None => RpcTransactionLogsFilter::All,
+                },
+                RpcTransactionLogsConfig {
+                    commitment: Some(commitment),
+                },
+            )
+            .await
+            .map(|(stream, unsubscribe)| (to_values(stream), unsubscribe)),
+    };
+
+    let (mut stream, unsubscribe) =
+        subscribed.map_err(|e| format!("Pubsub subscription failed: {}", e))?;
+
+    let mut idle_check = tokio::time::interval(IDLE_CHECK_INTERVAL);
+
+    loop {
+        tokio::select! {
+            item = stream.next() => match item {
+                Some(value) => {
+                    if sender.send(GatewayEvent::Notification(value)).is_err() {
+                        break;
+                    }
+                }
+                None => break,
+            },
+            _ = idle_check.tick() => {
+                if sender.receiver_count() == 0 {
+                    break;
+                }
+            }
+        }
+    }
+
+    drop(stream);
+    unsubscribe().await;
+    Ok(())
+}
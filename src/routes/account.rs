@@ -0,0 +1,261 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::Json as ResponseJson,
+};
+use futures::future::{join_all, try_join};
+use serde::Deserialize;
+use solana_account_decoder::UiAccountData;
+use solana_client::{
+    rpc_client::GetConfirmedSignaturesForAddress2Config, rpc_request::TokenAccountsFilter,
+};
+use solana_sdk::{native_token::lamports_to_sol, signature::Signature};
+use std::str::FromStr;
+
+use crate::{
+    decoder::decode_confirmed_transaction,
+    rpc::{confirmation_status_name, fetch_account, fetch_transaction, rpc_error},
+    state::AppState,
+    types::{
+        request::ClusterSelection,
+        response::{
+            AccountDetailsResponse, ApiResponse, BalanceResponse, SignatureHistoryResponse,
+            SignatureInfoResponse, TokenHoldingResponse, TokenHoldingsResponse,
+        },
+    },
+    utils::validate_pubkey,
+};
+
+const DEFAULT_SIGNATURE_LIMIT: usize = 10;
+const MAX_SIGNATURE_LIMIT: usize = 50;
+
+#[derive(Deserialize, Debug)]
+pub struct SignatureHistoryQuery {
+    pub before: Option<String>,
+    pub until: Option<String>,
+    pub limit: Option<String>,
+}
+
+fn parse_optional_signature(value: Option<String>) -> Result<Option<Signature>, String> {
+    match value {
+        Some(val) if !val.trim().is_empty() => Signature::from_str(val.trim())
+            .map(Some)
+            .map_err(|_| "Invalid signature".to_string()),
+        _ => Ok(None),
+    }
+}
+
+pub async fn handle_account_info(
+    State(state): State<AppState>,
+    ClusterSelection(cluster): ClusterSelection,
+    Path(pubkey): Path<String>,
+) -> ResponseJson<ApiResponse<AccountDetailsResponse>> {
+    println!("🔥 ACCOUNT INFO endpoint called with: {}", pubkey);
+
+    let pubkey = match validate_pubkey(&pubkey) {
+        Ok(pk) => pk,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let cluster = match state.clusters.resolve(cluster.as_deref()) {
+        Ok(cluster) => cluster,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let account = match fetch_account(&cluster.client, &pubkey).await {
+        Ok(account) => account,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let response = AccountDetailsResponse {
+        pubkey: pubkey.to_string(),
+        lamports: account.lamports,
+        owner: account.owner.to_string(),
+        executable: account.executable,
+        data_length: account.data.len(),
+        rent_epoch: account.rent_epoch,
+    };
+
+    ResponseJson(ApiResponse::success(response))
+}
+
+pub async fn handle_account_balance(
+    State(state): State<AppState>,
+    ClusterSelection(cluster): ClusterSelection,
+    Path(pubkey): Path<String>,
+) -> ResponseJson<ApiResponse<BalanceResponse>> {
+    println!("🔥 ACCOUNT BALANCE endpoint called with: {}", pubkey);
+
+    let pubkey = match validate_pubkey(&pubkey) {
+        Ok(pk) => pk,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let cluster = match state.clusters.resolve(cluster.as_deref()) {
+        Ok(cluster) => cluster,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let lamports = match cluster.client.get_balance(&pubkey).await {
+        Ok(lamports) => lamports,
+        Err(e) => return ResponseJson(ApiResponse::error(rpc_error(e))),
+    };
+
+    let response = BalanceResponse {
+        pubkey: pubkey.to_string(),
+        lamports,
+        sol: lamports_to_sol(lamports),
+    };
+
+    ResponseJson(ApiResponse::success(response))
+}
+
+pub async fn handle_account_tokens(
+    State(state): State<AppState>,
+    ClusterSelection(cluster): ClusterSelection,
+    Path(pubkey): Path<String>,
+) -> ResponseJson<ApiResponse<TokenHoldingsResponse>> {
+    println!("🔥 ACCOUNT TOKENS endpoint called with: {}", pubkey);
+
+    let owner = match validate_pubkey(&pubkey) {
+        Ok(pk) => pk,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let cluster = match state.clusters.resolve(cluster.as_deref()) {
+        Ok(cluster) => cluster,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    // Token-2022 accounts live under their own program, so both are queried.
+    let accounts = match try_join(
+        cluster
+            .client
+            .get_token_accounts_by_owner(&owner, TokenAccountsFilter::ProgramId(spl_token::id())),
+        cluster
+            .client
+            .get_token_accounts_by_owner(&owner, TokenAccountsFilter::ProgramId(spl_token_2022::id())),
+    )
+    .await
+    {
+        Ok((legacy, token_2022)) => legacy.into_iter().chain(token_2022),
+        Err(e) => return ResponseJson(ApiResponse::error(rpc_error(e))),
+    };
+
+    let tokens = accounts
+        .filter_map(|keyed| {
+            let UiAccountData::Json(parsed) = keyed.account.data else {
+                return None;
+            };
+            let info = parsed.parsed.get("info")?;
+            let token_amount = info.get("tokenAmount")?;
+
+            Some(TokenHoldingResponse {
+                address: keyed.pubkey,
+                program: keyed.account.owner,
+                mint: info.get("mint")?.as_str()?.to_string(),
+                amount: token_amount.get("amount")?.as_str()?.to_string(),
+                decimals: token_amount.get("decimals")?.as_u64()? as u8,
+                ui_amount: token_amount.get("uiAmountString")?.as_str()?.to_string(),
+            })
+        })
+        .collect();
+
+    let response = TokenHoldingsResponse {
+        owner: owner.to_string(),
+        tokens,
+    };
+
+    ResponseJson(ApiResponse::success(response))
+}
+
+pub async fn handle_account_signatures(
+    State(state): State<AppState>,
+    ClusterSelection(cluster): ClusterSelection,
+    Path(pubkey): Path<String>,
+    Query(query): Query<SignatureHistoryQuery>,
+) -> ResponseJson<ApiResponse<SignatureHistoryResponse>> {
+    println!("🔥 ACCOUNT SIGNATURES endpoint called with: {} {:?}", pubkey, query);
+
+    let address = match validate_pubkey(&pubkey) {
+        Ok(pk) => pk,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let before = match parse_optional_signature(query.before) {
+        Ok(sig) => sig,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let until = match parse_optional_signature(query.until) {
+        Ok(sig) => sig,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let limit = match query.limit.as_deref().map(|l| l.trim().parse::<usize>()) {
+        None => DEFAULT_SIGNATURE_LIMIT,
+        Some(Ok(val)) if val > 0 && val <= MAX_SIGNATURE_LIMIT => val,
+        Some(_) => {
+            return ResponseJson(ApiResponse::error(format!(
+                "Invalid limit: must be between 1 and {}",
+                MAX_SIGNATURE_LIMIT
+            )));
+        }
+    };
+
+    let cluster = match state.clusters.resolve(cluster.as_deref()) {
+        Ok(cluster) => cluster,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let client = &cluster.client;
+
+    let config = GetConfirmedSignaturesForAddress2Config {
+        before,
+        until,
+        limit: Some(limit),
+        commitment: Some(client.commitment()),
+    };
+
+    let statuses = match client.get_signatures_for_address_with_config(&address, config).await {
+        Ok(statuses) => statuses,
+        Err(e) => return ResponseJson(ApiResponse::error(rpc_error(e))),
+    };
+
+    let decoded = join_all(statuses.iter().map(|status| async move {
+        let signature = Signature::from_str(&status.signature).ok()?;
+        fetch_transaction(client, &signature)
+            .await
+            .ok()
+            .map(|tx| decode_confirmed_transaction(&tx))
+    }))
+    .await;
+
+    let next_before = if statuses.len() == limit {
+        statuses.last().map(|s| s.signature.clone())
+    } else {
+        None
+    };
+
+    let signatures = statuses
+        .into_iter()
+        .zip(decoded)
+        .map(|(status, instructions)| SignatureInfoResponse {
+            signature: status.signature,
+            slot: status.slot,
+            block_time: status.block_time,
+            status: if status.err.is_none() { "success" } else { "failed" }.to_string(),
+            confirmation_status: status.confirmation_status.as_ref().map(confirmation_status_name),
+            error: status.err.map(|e| e.to_string()),
+            memo: status.memo,
+            instructions: instructions.unwrap_or_default(),
+        })
+        .collect();
+
+    let response = SignatureHistoryResponse {
+        pubkey: address.to_string(),
+        signatures,
+        next_before,
+    };
+
+    ResponseJson(ApiResponse::success(response))
+}
@@ -0,0 +1,120 @@
+use axum::{extract::State, response::Json as ResponseJson};
+use serde::Deserialize;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use std::time::Duration;
+
+use crate::{
+    rpc::{confirmation_status_name, rpc_error},
+    state::AppState,
+    types::{
+        request::{ClusterSelection, SafeJson, get_required_string, get_required_u64},
+        response::{AirdropResponse, ApiResponse},
+    },
+    utils::{validate_amount, validate_pubkey},
+};
+
+const MAX_AIRDROP_LAMPORTS: u64 = 100 * LAMPORTS_PER_SOL;
+const CONFIRMATION_POLL_INTERVAL: Duration = Duration::from_millis(500);
+const CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Deserialize, Debug)]
+pub struct AirdropRequest {
+    pub pubkey: Option<String>,
+    pub lamports: Option<u64>,
+    pub cluster: Option<String>,
+}
+
+pub async fn handle_airdrop(
+    State(state): State<AppState>,
+    ClusterSelection(cluster): ClusterSelection,
+    SafeJson(payload): SafeJson<AirdropRequest>,
+) -> ResponseJson<ApiResponse<AirdropResponse>> {
+    println!("🔥 AIRDROP endpoint called with: {:?}", payload);
+
+    let req = match payload {
+        Some(req) => req,
+        None => {
+            return ResponseJson(ApiResponse::error("Missing required fields".to_string()));
+        }
+    };
+
+    let pubkey_str = match get_required_string(req.pubkey, "pubkey") {
+        Ok(val) => val,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let lamports = match get_required_u64(req.lamports, "lamports") {
+        Ok(val) => val,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let validated_lamports = match validate_amount(lamports, Some(MAX_AIRDROP_LAMPORTS)) {
+        Ok(val) => val,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let pubkey = match validate_pubkey(&pubkey_str) {
+        Ok(pk) => pk,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let cluster = match state.clusters.resolve(req.cluster.as_deref().or(cluster.as_deref())) {
+        Ok(cluster) => cluster,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+    let client = &cluster.client;
+
+    match cluster.is_mainnet().await {
+        Ok(false) => {}
+        Ok(true) => {
+            return ResponseJson(ApiResponse::error("Airdrops are not available on mainnet".to_string()));
+        }
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    }
+
+    let signature = match client.request_airdrop(&pubkey, validated_lamports).await {
+        Ok(sig) => sig,
+        Err(e) => return ResponseJson(ApiResponse::error(rpc_error(e))),
+    };
+
+    let deadline = tokio::time::Instant::now() + CONFIRMATION_TIMEOUT;
+    let confirmation_status = loop {
+        match client.get_signature_statuses(&[signature]).await {
+            Ok(response) => {
+                if let Some(Some(status)) = response.value.into_iter().next() {
+                    if let Some(err) = status.err {
+                        return ResponseJson(ApiResponse::error(format!(
+                            "Airdrop {} failed: {}",
+                            signature, err
+                        )));
+                    }
+                    if status.satisfies_commitment(client.commitment()) {
+                        break status
+                            .confirmation_status
+                            .as_ref()
+                            .map(confirmation_status_name)
+                            .unwrap_or_else(|| "confirmed".to_string());
+                    }
+                }
+            }
+            Err(e) => return ResponseJson(ApiResponse::error(rpc_error(e))),
+        }
+
+        if tokio::time::Instant::now() >= deadline {
+            return ResponseJson(ApiResponse::error(format!(
+                "Airdrop {} was not confirmed in time",
+                signature
+            )));
+        }
+        tokio::time::sleep(CONFIRMATION_POLL_INTERVAL).await;
+    };
+
+    let response = AirdropResponse {
+        signature: signature.to_string(),
+        pubkey: pubkey.to_string(),
+        lamports: validated_lamports,
+        confirmation_status,
+    };
+
+    ResponseJson(ApiResponse::success(response))
+}
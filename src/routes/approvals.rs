@@ -0,0 +1,353 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::Json as ResponseJson,
+};
+use base64::{engine::general_purpose, Engine as _};
+use serde::Deserialize;
+
+use crate::{
+    approvals::{
+        is_expired, ApprovalAction, ApprovalExpiry, ApprovalRequest, ApprovalStatus,
+    },
+    decoder::decode_message_instructions,
+    policy::{SigningPolicy, SigningRequest},
+    routes::{keys::key_response, transaction::sign_transaction},
+    state::AppState,
+    types::{
+        request::{Principal, SafeJson},
+        response::{
+            ApiResponse, ApprovalExecuteResponse, ApprovalResponse, KeyResponse,
+            TransactionSignResponse,
+        },
+    },
+    utils::unix_now,
+};
+
+#[derive(Deserialize, Debug)]
+pub struct ApprovalListQuery {
+    pub status: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ApprovalRejectRequest {
+    pub reason: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ApprovalExecuteRequest {
+    pub password: Option<String>,
+}
+
+pub fn approval_response(request: &ApprovalRequest) -> ApprovalResponse {
+    let mut response = ApprovalResponse {
+        id: request.id.clone(),
+        kind: "transaction",
+        status: request.status,
+        key_id: request.key_id.clone(),
+        pubkey: request.pubkey.clone(),
+        cluster: None,
+        quorum: request.quorum,
+        approvals: request.approvals.clone(),
+        requested_by: request.requested_by.clone(),
+        rejected_by: request.rejected_by.clone(),
+        rejection: request.rejection.clone(),
+        lamports: None,
+        tokens: None,
+        expiry: request.expiry.clone(),
+        created_at: request.created_at,
+        finished_at: request.finished_at,
+        signature: request.signature.clone(),
+        transaction: None,
+        instructions: None,
+        policy: None,
+    };
+
+    match &request.action {
+        ApprovalAction::Transaction {
+            cluster,
+            transaction,
+            lamports,
+            tokens,
+        } => {
+            response.cluster = Some(cluster.clone());
+            response.lamports = Some(*lamports);
+            response.tokens = Some(tokens.clone());
+            response.transaction = Some(
+                bincode::serialize(transaction)
+                    .map(|bytes| general_purpose::STANDARD.encode(bytes))
+                    .unwrap_or_default(),
+            );
+            response.instructions = Some(decode_message_instructions(&transaction.message, None));
+        }
+        ApprovalAction::Policy(policy) => {
+            response.kind = if policy.is_some() { "policy_set" } else { "policy_clear" };
+            response.policy = policy.clone();
+        }
+    }
+
+    response
+}
+
+pub fn required_principal(principal: Option<String>) -> Result<String, String> {
+    principal.ok_or_else(|| "API key required".to_string())
+}
+
+async fn record(
+    state: &AppState,
+    request: &ApprovalRequest,
+    action: &str,
+    actor: Option<&str>,
+    detail: String,
+) {
+    if let Some(stored) = state.keystore.resolve(&request.key_id).await {
+        state.keystore.record_event(&stored, action, actor, Some(detail));
+    }
+}
+
+pub async fn handle_approval_list(
+    State(state): State<AppState>,
+    Query(query): Query<ApprovalListQuery>,
+) -> ResponseJson<ApiResponse<Vec<ApprovalResponse>>> {
+    println!("🔥 APPROVAL LIST endpoint called with: {:?}", query);
+
+    let status = match query.status.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+        Some(status) => match ApprovalStatus::parse(status) {
+            Ok(status) => Some(status),
+            Err(e) => return ResponseJson(ApiResponse::error(e)),
+        },
+        None => None,
+    };
+
+    let approvals = state
+        .approvals
+        .list(status)
+        .await
+        .iter()
+        .map(approval_response)
+        .collect();
+
+    ResponseJson(ApiResponse::success(approvals))
+}
+
+pub async fn handle_approval_get(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ResponseJson<ApiResponse<ApprovalResponse>> {
+    println!("🔥 APPROVAL GET endpoint called with: {}", id);
+
+    match state.approvals.get(&id).await {
+        Some(request) => ResponseJson(ApiResponse::success(approval_response(&request))),
+        None => ResponseJson(ApiResponse::error("Approval not found".to_string())),
+    }
+}
+
+pub async fn handle_approval_approve(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Principal(principal): Principal,
+) -> ResponseJson<ApiResponse<ApprovalResponse>> {
+    println!("🔥 APPROVAL APPROVE endpoint called with: {} {:?}", id, principal);
+
+    let principal = match required_principal(principal) {
+        Ok(principal) => principal,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let request = match state.approvals.approve(&id, &principal).await {
+        Ok(request) => request,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    record(
+        &state,
+        &request,
+        "approval_granted",
+        Some(&principal),
+        format!(
+            "approval {} ({} of {})",
+            request.id,
+            request.approvals.len(),
+            request.quorum
+        ),
+    )
+    .await;
+
+    ResponseJson(ApiResponse::success(approval_response(&request)))
+}
+
+pub async fn handle_approval_reject(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Principal(principal): Principal,
+    SafeJson(payload): SafeJson<ApprovalRejectRequest>,
+) -> ResponseJson<ApiResponse<ApprovalResponse>> {
+    println!("🔥 APPROVAL REJECT endpoint called with: {} {:?} {:?}", id, principal, payload);
+
+    let principal = match required_principal(principal) {
+        Ok(principal) => principal,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let reason = payload
+        .and_then(|req| req.reason)
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty());
+
+    let request = match state.approvals.reject(&id, &principal, reason).await {
+        Ok(request) => request,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    record(
+        &state,
+        &request,
+        "approval_rejected",
+        Some(&principal),
+        match &request.rejection {
+            Some(reason) => format!("approval {}: {}", request.id, reason),
+            None => format!("approval {}", request.id),
+        },
+    )
+    .await;
+
+    ResponseJson(ApiResponse::success(approval_response(&request)))
+}
+
+pub async fn handle_approval_execute(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Principal(principal): Principal,
+    SafeJson(payload): SafeJson<ApprovalExecuteRequest>,
+) -> ResponseJson<ApiResponse<ApprovalExecuteResponse>> {
+    println!("🔥 APPROVAL EXECUTE endpoint called with: {} {:?}", id, principal);
+
+    let password = payload.and_then(|req| req.password);
+
+    let request = match state.approvals.begin_execution(&id).await {
+        Ok(request) => request,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let result = match &request.action {
+        ApprovalAction::Transaction { .. } => {
+            execute_transaction(&state, &request, password, principal.as_deref())
+                .await
+                .map(|signed| ApprovalExecuteResponse::Transaction(Box::new(signed)))
+        }
+        ApprovalAction::Policy(policy) => {
+            execute_policy(&state, &request, policy.clone(), password, principal.as_deref())
+                .await
+                .map(|key| ApprovalExecuteResponse::Key(Box::new(key)))
+        }
+    };
+
+    let mut response = match result {
+        Ok(response) => response,
+        Err(e) => {
+            state.approvals.abort_execution(&id).await;
+            return ResponseJson(ApiResponse::error(e));
+        }
+    };
+
+    let signature = match &response {
+        ApprovalExecuteResponse::Transaction(signed) => signed.signature.clone(),
+        ApprovalExecuteResponse::Key(_) => None,
+    };
+    let executed = state.approvals.complete_execution(&id, signature.clone()).await;
+
+    record(
+        &state,
+        &request,
+        "approval_executed",
+        principal.as_deref(),
+        match &signature {
+            Some(signature) => format!("approval {} signature {}", request.id, signature),
+            None => format!("approval {}", request.id),
+        },
+    )
+    .await;
+
+    let approval = executed.as_ref().map(approval_response);
+    match &mut response {
+        ApprovalExecuteResponse::Transaction(signed) => signed.approval = approval,
+        ApprovalExecuteResponse::Key(key) => key.approval = approval,
+    }
+    ResponseJson(ApiResponse::success(response))
+}
+
+async fn check_expiry(state: &AppState, request: &ApprovalRequest) -> Result<(), String> {
+    let expired = match request.cluster() {
+        Some(cluster) => {
+            let cluster = state.clusters.resolve(Some(cluster))?;
+            is_expired(cluster, &request.expiry, None).await?
+        }
+        None => match request.expiry {
+            ApprovalExpiry::Deadline { expires_at } => unix_now() >= expires_at,
+            _ => false,
+        },
+    };
+
+    if expired {
+        if let Some(expired) = state.approvals.expire(&request.id).await {
+            let detail = format!("approval {}", expired.id);
+            record(state, &expired, "approval_expired", None, detail).await;
+        }
+        return Err("Approval expired".to_string());
+    }
+    Ok(())
+}
+
+// The blockhash or nonce is checked again right before signing, since the
+// background pass only runs every few seconds.
+async fn execute_transaction(
+    state: &AppState,
+    request: &ApprovalRequest,
+    password: Option<String>,
+    actor: Option<&str>,
+) -> Result<TransactionSignResponse, String> {
+    let ApprovalAction::Transaction { transaction, .. } = &request.action else {
+        return Err("Approval is not a transaction".to_string());
+    };
+
+    check_expiry(state, request).await?;
+
+    let mut transaction = transaction.clone();
+    let keypair = state
+        .keystore
+        .signer(
+            None,
+            None,
+            Some(request.key_id.clone()),
+            password,
+            actor,
+            SigningRequest::ApprovedTransaction(&transaction.message),
+        )
+        .await?;
+
+    sign_transaction(&mut transaction, &keypair)
+}
+
+// Applying an approved policy change still needs the same credentials as
+// setting a policy directly.
+async fn execute_policy(
+    state: &AppState,
+    request: &ApprovalRequest,
+    policy: Option<SigningPolicy>,
+    password: Option<String>,
+    principal: Option<&str>,
+) -> Result<KeyResponse, String> {
+    check_expiry(state, request).await?;
+
+    let stored = state
+        .keystore
+        .resolve(&request.key_id)
+        .await
+        .ok_or_else(|| "Key not found".to_string())?;
+    state.keystore.authenticate(&stored, password, principal).await?;
+
+    let updated = state
+        .keystore
+        .set_policy(&stored.id, policy, principal)
+        .await?;
+    Ok(key_response(updated))
+}
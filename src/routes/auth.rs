@@ -0,0 +1,176 @@
+use axum::{extract::State, http::HeaderMap, response::Json as ResponseJson};
+use serde::Deserialize;
+
+use crate::{
+    siws::{Session, SiwsMessage},
+    state::AppState,
+    types::{
+        request::{SafeJson, get_required_string},
+        response::{ApiResponse, SiwsChallengeResponse, SiwsSessionResponse},
+    },
+    utils::{parse_signature, signable_message, validate_pubkey},
+};
+
+#[derive(Deserialize, Debug, Default)]
+pub struct SiwsChallengeRequest {
+    pub domain: Option<String>,
+    pub address: Option<String>,
+    pub statement: Option<String>,
+    pub uri: Option<String>,
+    #[serde(rename = "chainId")]
+    pub chain_id: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SiwsVerifyRequest {
+    pub message: Option<String>,
+    pub signature: Option<String>,
+    pub format: Option<String>,
+    #[serde(rename = "signatureEncoding")]
+    pub signature_encoding: Option<String>,
+}
+
+fn session_response(token: Option<String>, session: Session) -> SiwsSessionResponse {
+    SiwsSessionResponse {
+        token,
+        address: session.address,
+        domain: session.domain,
+        issued_at: session.issued_at,
+        expires_at: session.expires_at,
+    }
+}
+
+pub async fn handle_siws_challenge(
+    State(state): State<AppState>,
+    SafeJson(payload): SafeJson<SiwsChallengeRequest>,
+) -> ResponseJson<ApiResponse<SiwsChallengeResponse>> {
+    println!("🔥 SIWS CHALLENGE endpoint called with: {:?}", payload);
+
+    let req = payload.unwrap_or_default();
+
+    let address = match req.address.as_deref().map(str::trim).filter(|a| !a.is_empty()) {
+        Some(address) => match validate_pubkey(address) {
+            Ok(pubkey) => Some(pubkey.to_string()),
+            Err(e) => return ResponseJson(ApiResponse::error(e)),
+        },
+        None => None,
+    };
+
+    let non_empty = |value: Option<String>| {
+        value
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+
+    let message = match state
+        .siws
+        .challenge(
+            req.domain.as_deref(),
+            address.clone(),
+            non_empty(req.statement),
+            non_empty(req.uri),
+            non_empty(req.chain_id),
+        )
+        .await
+    {
+        Ok(message) => message,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    // Without an address the wallet renders the message itself from these
+    // fields, as `signIn` does.
+    let rendered = address.as_ref().map(|_| message.render());
+
+    ResponseJson(ApiResponse::success(SiwsChallengeResponse {
+        domain: message.domain,
+        address,
+        statement: message.statement,
+        uri: message.uri,
+        version: message.version,
+        chain_id: message.chain_id,
+        nonce: message.nonce,
+        issued_at: message.issued_at,
+        expiration_time: message.expiration_time,
+        message: rendered,
+    }))
+}
+
+pub async fn handle_siws_verify(
+    State(state): State<AppState>,
+    SafeJson(payload): SafeJson<SiwsVerifyRequest>,
+) -> ResponseJson<ApiResponse<SiwsSessionResponse>> {
+    println!("🔥 SIWS VERIFY endpoint called with: {:?}", payload);
+
+    let req = match payload {
+        Some(req) => req,
+        None => {
+            return ResponseJson(ApiResponse::error("Missing required fields".to_string()));
+        }
+    };
+
+    let message_str = match get_required_string(req.message, "message") {
+        Ok(val) => val,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let signature_str = match get_required_string(req.signature, "signature") {
+        Ok(val) => val,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let message = match SiwsMessage::parse(&message_str) {
+        Ok(message) => message,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let pubkey = match validate_pubkey(&message.address) {
+        Ok(pk) => pk,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let signature = match parse_signature(&signature_str, req.signature_encoding.as_deref()) {
+        Ok(sig) => sig,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    if let Err(e) = state.siws.check(&message).await {
+        return ResponseJson(ApiResponse::error(e));
+    }
+
+    let (signed_bytes, _) = match signable_message(message_str.as_bytes(), req.format.as_deref()) {
+        Ok(signable) => signable,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    if !signature.verify(&pubkey.to_bytes(), &signed_bytes) {
+        return ResponseJson(ApiResponse::error("Invalid signature".to_string()));
+    }
+
+    match state.siws.redeem(&message).await {
+        Ok((token, session)) => ResponseJson(ApiResponse::success(session_response(Some(token), session))),
+        Err(e) => ResponseJson(ApiResponse::error(e)),
+    }
+}
+
+pub async fn handle_siws_session(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> ResponseJson<ApiResponse<SiwsSessionResponse>> {
+    println!("🔥 SIWS SESSION endpoint called");
+
+    let token = headers
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|t| !t.is_empty());
+
+    let Some(token) = token else {
+        return ResponseJson(ApiResponse::error("Missing session token".to_string()));
+    };
+
+    match state.siws.session(token) {
+        Some(session) => ResponseJson(ApiResponse::success(session_response(None, session))),
+        None => ResponseJson(ApiResponse::error("Invalid or expired session".to_string())),
+    }
+}
@@ -0,0 +1,52 @@
+use axum::{extract::State, response::Json as ResponseJson};
+
+use crate::{
+    state::AppState,
+    types::{
+        request::ClusterSelection,
+        response::{ApiResponse, BlockhashResponse, ClusterResponse},
+    },
+};
+
+pub async fn handle_cluster_list(
+    State(state): State<AppState>,
+) -> ResponseJson<ApiResponse<Vec<ClusterResponse>>> {
+    println!("🔥 CLUSTER LIST endpoint called");
+
+    let clusters = state
+        .clusters
+        .iter()
+        .map(|cluster| ClusterResponse {
+            name: cluster.name.clone(),
+            kind: cluster.kind,
+            commitment: cluster.commitment.commitment.to_string(),
+            default: cluster.name == state.clusters.default_name(),
+        })
+        .collect();
+
+    ResponseJson(ApiResponse::success(clusters))
+}
+
+pub async fn handle_latest_blockhash(
+    State(state): State<AppState>,
+    ClusterSelection(cluster): ClusterSelection,
+) -> ResponseJson<ApiResponse<BlockhashResponse>> {
+    println!("🔥 BLOCKHASH endpoint called with: {:?}", cluster);
+
+    let cluster = match state.clusters.resolve(cluster.as_deref()) {
+        Ok(cluster) => cluster,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let latest = match state.blockhash.latest(cluster).await {
+        Ok(latest) => latest,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    ResponseJson(ApiResponse::success(BlockhashResponse {
+        cluster: cluster.name.clone(),
+        blockhash: latest.blockhash.to_string(),
+        last_valid_block_height: latest.last_valid_block_height,
+        age_ms: latest.fetched_at.elapsed().as_millis(),
+    }))
+}
@@ -0,0 +1,150 @@
+use axum::{extract::State, response::Json as ResponseJson};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use solana_sdk::instruction::Instruction;
+use std::collections::HashMap;
+
+use crate::{
+    idl::build_instruction,
+    state::AppState,
+    types::{
+        request::{SafeJson, get_required_string},
+        response::{AccountInfo, ApiResponse, InstructionResponse},
+    },
+    utils::{
+        account_metas_from_info, convert_instruction_to_response, decode_bytes,
+        validate_account_pubkey, validate_instruction_size, validate_pubkey,
+    },
+};
+
+const MAX_RAW_ACCOUNTS: usize = 64;
+
+#[derive(Deserialize, Debug)]
+pub struct AnchorInstructionRequest {
+    pub idl: Option<String>,
+    #[serde(rename = "programId")]
+    pub program_id: Option<String>,
+    pub instruction: Option<String>,
+    pub args: Option<Map<String, Value>>,
+    pub accounts: Option<HashMap<String, String>>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct RawInstructionRequest {
+    #[serde(alias = "programId")]
+    pub program_id: Option<String>,
+    pub accounts: Option<Vec<AccountInfo>>,
+    pub data: Option<String>,
+    pub encoding: Option<String>,
+}
+
+pub async fn handle_anchor_instruction(
+    State(state): State<AppState>,
+    SafeJson(payload): SafeJson<AnchorInstructionRequest>,
+) -> ResponseJson<ApiResponse<InstructionResponse>> {
+    println!("🔥 ANCHOR INSTRUCTION endpoint called with: {:?}", payload);
+
+    let req = match payload {
+        Some(req) => req,
+        None => {
+            return ResponseJson(ApiResponse::error("Missing required fields".to_string()));
+        }
+    };
+
+    let instruction_name = match get_required_string(req.instruction, "instruction") {
+        Ok(val) => val,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let selector = match req.idl.as_deref().or(req.program_id.as_deref()) {
+        Some(val) if !val.trim().is_empty() => val.trim().to_string(),
+        _ => return ResponseJson(ApiResponse::error("Missing required fields".to_string())),
+    };
+
+    let idl = match state.idls.find(&selector) {
+        Ok(idl) => idl,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let program_id_str = match req.program_id.as_deref().or(idl.program_address()) {
+        Some(val) => val.to_string(),
+        None => return ResponseJson(ApiResponse::error("Missing required fields".to_string())),
+    };
+
+    let program_id = match validate_pubkey(&program_id_str) {
+        Ok(pk) => pk,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let args = req.args.unwrap_or_default();
+    let accounts = req.accounts.unwrap_or_default();
+
+    let instruction =
+        match build_instruction(&idl, &program_id, &instruction_name, &args, &accounts) {
+            Ok(ix) => ix,
+            Err(e) => return ResponseJson(ApiResponse::error(e)),
+        };
+
+    let response = convert_instruction_to_response(instruction);
+    ResponseJson(ApiResponse::success(response))
+}
+
+pub async fn handle_raw_instruction(
+    SafeJson(payload): SafeJson<RawInstructionRequest>,
+) -> ResponseJson<ApiResponse<InstructionResponse>> {
+    println!("🔥 RAW INSTRUCTION endpoint called with: {:?}", payload);
+
+    let req = match payload {
+        Some(req) => req,
+        None => {
+            return ResponseJson(ApiResponse::error("Missing required fields".to_string()));
+        }
+    };
+
+    let program_id_str = match get_required_string(req.program_id, "program_id") {
+        Ok(val) => val,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let accounts = match req.accounts {
+        Some(accounts) => accounts,
+        None => return ResponseJson(ApiResponse::error("Missing required fields".to_string())),
+    };
+
+    if accounts.len() > MAX_RAW_ACCOUNTS {
+        return ResponseJson(ApiResponse::error("Too many accounts".to_string()));
+    }
+
+    let encoding = req.encoding.unwrap_or_else(|| "base64".to_string());
+
+    let data = match req.data {
+        Some(val) => match decode_bytes(&val, &encoding) {
+            Ok(bytes) => bytes,
+            Err(e) => return ResponseJson(ApiResponse::error(e)),
+        },
+        None => Vec::new(),
+    };
+
+    let program_id = match validate_account_pubkey(&program_id_str) {
+        Ok(pk) => pk,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let metas = match account_metas_from_info(&accounts, &program_id) {
+        Ok(metas) => metas,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let instruction = Instruction {
+        program_id,
+        accounts: metas,
+        data,
+    };
+
+    if let Err(e) = validate_instruction_size(&instruction) {
+        return ResponseJson(ApiResponse::error(e));
+    }
+
+    let response = convert_instruction_to_response(instruction);
+    ResponseJson(ApiResponse::success(response))
+}
@@ -1,16 +1,464 @@
-use axum::response::Json as ResponseJson;
-use solana_sdk::signature::{Keypair, Signer};
+use axum::{
+    extract::{Path, State},
+    response::Json as ResponseJson,
+};
+use base64::{engine::general_purpose, Engine as _};
+use bip39::{Language, Mnemonic, MnemonicType};
+use serde::Deserialize;
+use solana_sdk::{
+    derivation_path::DerivationPath,
+    signature::{Keypair, Signer},
+};
+use std::{
+    sync::{Arc, atomic::Ordering},
+    time::{Duration, Instant},
+};
 
-use crate::types::response::{ApiResponse, KeypairResponse};
+use crate::{
+    state::AppState,
+    types::{
+        request::{SafeJson, get_required_string},
+        response::{
+            ApiResponse, DerivedAddressResponse, DerivedKeypairResponse, KeypairConvertResponse,
+            KeypairResponse, VanityResponse,
+        },
+    },
+    utils::{
+        decode_bytes, derive_keypair, mnemonic_seed, parse_mnemonic,
+        parse_secret_key, render_derivation_path,
+    },
+    vanity::{SearchProgress, VanityJob, VanityPattern, VanityStatus},
+};
 
-pub async fn handle_keypair_generation() -> ResponseJson<ApiResponse<KeypairResponse>> {
+const MAX_RECOVER_COUNT: u32 = 20;
+const MAX_DERIVE_COUNT: u32 = 1000;
+const HARDENED_OFFSET: u32 = 1 << 31;
+const DEFAULT_PATH_TEMPLATE: &str = "m/44'/501'/{account}'/{change}'";
+const DEFAULT_VANITY_TIMEOUT_SECS: u64 = 30;
+const MAX_VANITY_TIMEOUT_SECS: u64 = 60;
+const MAX_VANITY_JOB_TIMEOUT_SECS: u64 = 3600;
+
+#[derive(Deserialize, Debug)]
+pub struct KeypairRequest {
+    pub mnemonic: Option<bool>,
+    pub words: Option<u32>,
+    pub passphrase: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct KeypairRecoverRequest {
+    pub mnemonic: Option<String>,
+    pub passphrase: Option<String>,
+    pub start: Option<u32>,
+    pub count: Option<u32>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct KeypairConvertRequest {
+    pub secret: Option<String>,
+    pub format: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct KeypairVanityRequest {
+    pub prefix: Option<String>,
+    pub suffix: Option<String>,
+    #[serde(rename = "caseSensitive")]
+    pub case_sensitive: Option<bool>,
+    #[serde(rename = "timeoutSecs")]
+    pub timeout_secs: Option<u64>,
+    #[serde(rename = "async")]
+    pub run_async: Option<bool>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct IndexRange {
+    pub start: Option<u32>,
+    pub count: Option<u32>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct KeypairDeriveRequest {
+    pub mnemonic: Option<String>,
+    pub passphrase: Option<String>,
+    pub seed: Option<String>,
+    #[serde(rename = "seedEncoding")]
+    pub seed_encoding: Option<String>,
+    pub path: Option<String>,
+    pub account: Option<IndexRange>,
+    pub change: Option<IndexRange>,
+    #[serde(rename = "includeSecret")]
+    pub include_secret: Option<bool>,
+}
+
+fn index_range(
+    range: Option<IndexRange>,
+    placeholder: &str,
+    template: &str,
+) -> Result<(u32, u32), String> {
+    let (start, count) = match range {
+        Some(range) => (range.start.unwrap_or(0), range.count.unwrap_or(1)),
+        None => (0, 1),
+    };
+
+    if count == 0 {
+        return Err("Invalid count: must be at least 1".to_string());
+    }
+
+    if !template.contains(placeholder) && (start != 0 || count != 1) {
+        return Err(format!("Invalid range: path has no {} placeholder", placeholder));
+    }
+
+    if start.checked_add(count).is_none_or(|end| end > HARDENED_OFFSET) {
+        return Err("Invalid start: index out of range".to_string());
+    }
+
+    Ok((start, count))
+}
+
+pub async fn handle_keypair_generation(
+    SafeJson(payload): SafeJson<KeypairRequest>,
+) -> ResponseJson<ApiResponse<KeypairResponse>> {
     println!("🔥 KEYPAIR endpoint called");
-    
-    let keypair = Keypair::new();
+
+    let req = match payload {
+        Some(req) if req.mnemonic.unwrap_or(false) || req.words.is_some() => req,
+        _ => {
+            let keypair = Keypair::new();
+            let response = KeypairResponse {
+                pubkey: keypair.pubkey().to_string(),
+                secret: bs58::encode(keypair.to_bytes()).into_string(),
+                mnemonic: None,
+                derivation_path: None,
+            };
+
+            return ResponseJson(ApiResponse::success(response));
+        }
+    };
+
+    let mnemonic_type = match req.words.unwrap_or(12) {
+        12 => MnemonicType::Words12,
+        24 => MnemonicType::Words24,
+        _ => return ResponseJson(ApiResponse::error("Invalid words: must be 12 or 24".to_string())),
+    };
+
+    let mnemonic = Mnemonic::new(mnemonic_type, Language::English);
+    let seed = mnemonic_seed(&mnemonic, req.passphrase.as_deref().unwrap_or(""));
+    let path = DerivationPath::new_bip44(Some(0), Some(0));
+
+    let keypair = match derive_keypair(&seed, &path) {
+        Ok(kp) => kp,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
     let response = KeypairResponse {
         pubkey: keypair.pubkey().to_string(),
         secret: bs58::encode(keypair.to_bytes()).into_string(),
+        mnemonic: Some(mnemonic.phrase().to_string()),
+        derivation_path: Some(format!("{:?}", path)),
+    };
+
+    ResponseJson(ApiResponse::success(response))
+}
+
+pub async fn handle_keypair_recover(
+    SafeJson(payload): SafeJson<KeypairRecoverRequest>,
+) -> ResponseJson<ApiResponse<Vec<DerivedKeypairResponse>>> {
+    println!("🔥 KEYPAIR RECOVER endpoint called");
+
+    let req = match payload {
+        Some(req) => req,
+        None => {
+            return ResponseJson(ApiResponse::error("Missing required fields".to_string()));
+        }
+    };
+
+    let phrase = match get_required_string(req.mnemonic, "mnemonic") {
+        Ok(val) => val,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let mnemonic = match parse_mnemonic(&phrase) {
+        Ok(m) => m,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let start = req.start.unwrap_or(0);
+    let count = req.count.unwrap_or(1);
+
+    if count == 0 || count > MAX_RECOVER_COUNT {
+        return ResponseJson(ApiResponse::error(format!(
+            "Invalid count: must be between 1 and {}",
+            MAX_RECOVER_COUNT
+        )));
+    }
+
+    if start.checked_add(count).is_none_or(|end| end > HARDENED_OFFSET) {
+        return ResponseJson(ApiResponse::error("Invalid start: index out of range".to_string()));
+    }
+
+    let seed = mnemonic_seed(&mnemonic, req.passphrase.as_deref().unwrap_or(""));
+    let mut accounts = Vec::with_capacity(count as usize);
+
+    for index in start..start + count {
+        let path = DerivationPath::new_bip44(Some(index), Some(0));
+
+        let keypair = match derive_keypair(&seed, &path) {
+            Ok(kp) => kp,
+            Err(e) => return ResponseJson(ApiResponse::error(e)),
+        };
+
+        accounts.push(DerivedKeypairResponse {
+            index,
+            derivation_path: format!("{:?}", path),
+            pubkey: keypair.pubkey().to_string(),
+            secret: Some(bs58::encode(keypair.to_bytes()).into_string()),
+        });
+    }
+
+    ResponseJson(ApiResponse::success(accounts))
+}
+
+pub async fn handle_keypair_derive(
+    SafeJson(payload): SafeJson<KeypairDeriveRequest>,
+) -> ResponseJson<ApiResponse<Vec<DerivedAddressResponse>>> {
+    println!("🔥 KEYPAIR DERIVE endpoint called");
+
+    let req = match payload {
+        Some(req) => req,
+        None => {
+            return ResponseJson(ApiResponse::error("Missing required fields".to_string()));
+        }
+    };
+
+    let seed = match (req.mnemonic, req.seed) {
+        (Some(phrase), None) if !phrase.trim().is_empty() => match parse_mnemonic(&phrase) {
+            Ok(mnemonic) => mnemonic_seed(&mnemonic, req.passphrase.as_deref().unwrap_or("")),
+            Err(e) => return ResponseJson(ApiResponse::error(e)),
+        },
+        (None, Some(seed)) if !seed.trim().is_empty() => {
+            let encoding = req.seed_encoding.unwrap_or_else(|| "hex".to_string());
+            match decode_bytes(&seed, &encoding) {
+                Ok(bytes) if (16..=64).contains(&bytes.len()) => bytes,
+                Ok(_) => {
+                    return ResponseJson(ApiResponse::error(
+                        "Invalid seed: must be between 16 and 64 bytes".to_string(),
+                    ));
+                }
+                Err(e) => return ResponseJson(ApiResponse::error(e)),
+            }
+        }
+        (Some(_), Some(_)) => {
+            return ResponseJson(ApiResponse::error(
+                "Provide either mnemonic or seed, not both".to_string(),
+            ));
+        }
+        _ => return ResponseJson(ApiResponse::error("Missing required fields".to_string())),
+    };
+
+    let template = req.path.unwrap_or_else(|| DEFAULT_PATH_TEMPLATE.to_string());
+
+    if let Err(e) = render_derivation_path(&template, 0, 0) {
+        return ResponseJson(ApiResponse::error(e));
+    }
+
+    let (account_start, account_count) = match index_range(req.account, "{account}", &template) {
+        Ok(range) => range,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let (change_start, change_count) = match index_range(req.change, "{change}", &template) {
+        Ok(range) => range,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    if account_count.saturating_mul(change_count) > MAX_DERIVE_COUNT {
+        return ResponseJson(ApiResponse::error(format!(
+            "Too many keys: at most {} per request",
+            MAX_DERIVE_COUNT
+        )));
+    }
+
+    let include_secret = req.include_secret.unwrap_or(false);
+    let mut derived = Vec::with_capacity((account_count * change_count) as usize);
+
+    for account in account_start..account_start + account_count {
+        for change in change_start..change_start + change_count {
+            let path = match render_derivation_path(&template, account, change) {
+                Ok(path) => path,
+                Err(e) => return ResponseJson(ApiResponse::error(e)),
+            };
+
+            let keypair = match derive_keypair(&seed, &path) {
+                Ok(kp) => kp,
+                Err(e) => return ResponseJson(ApiResponse::error(e)),
+            };
+
+            derived.push(DerivedAddressResponse {
+                account,
+                change,
+                derivation_path: format!("{:?}", path),
+                pubkey: keypair.pubkey().to_string(),
+                secret: include_secret.then(|| bs58::encode(keypair.to_bytes()).into_string()),
+            });
+        }
+    }
+
+    ResponseJson(ApiResponse::success(derived))
+}
+
+pub async fn handle_keypair_convert(
+    SafeJson(payload): SafeJson<KeypairConvertRequest>,
+) -> ResponseJson<ApiResponse<KeypairConvertResponse>> {
+    println!("🔥 KEYPAIR CONVERT endpoint called");
+
+    let req = match payload {
+        Some(req) => req,
+        None => {
+            return ResponseJson(ApiResponse::error("Missing required fields".to_string()));
+        }
+    };
+
+    let secret = match get_required_string(req.secret, "secret") {
+        Ok(val) => val,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let keypair = match parse_secret_key(&secret, req.format.as_deref()) {
+        Ok(kp) => kp,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let bytes = keypair.to_bytes();
+
+    let response = KeypairConvertResponse {
+        pubkey: keypair.pubkey().to_string(),
+        base58: bs58::encode(&bytes).into_string(),
+        json: bytes.to_vec(),
+        hex: hex::encode(bytes),
+        base64: general_purpose::STANDARD.encode(bytes),
+        seed: hex::encode(&bytes[..32]),
     };
 
     ResponseJson(ApiResponse::success(response))
 }
+
+fn vanity_response(job: VanityJob) -> VanityResponse {
+    VanityResponse {
+        id: Some(job.id.clone()),
+        status: job.status,
+        prefix: job.pattern.prefix.clone(),
+        suffix: job.pattern.suffix.clone(),
+        case_sensitive: job.pattern.case_sensitive,
+        attempts: job.progress.attempts.load(Ordering::Relaxed),
+        estimated_attempts: job.pattern.estimated_attempts(),
+        elapsed_ms: job.elapsed().as_millis(),
+        timeout_ms: job.timeout.as_millis(),
+        pubkey: job.pubkey,
+        secret: job.secret,
+    }
+}
+
+pub async fn handle_keypair_vanity(
+    State(state): State<AppState>,
+    SafeJson(payload): SafeJson<KeypairVanityRequest>,
+) -> ResponseJson<ApiResponse<VanityResponse>> {
+    println!("🔥 KEYPAIR VANITY endpoint called with: {:?}", payload);
+
+    let req = match payload {
+        Some(req) => req,
+        None => {
+            return ResponseJson(ApiResponse::error("Missing required fields".to_string()));
+        }
+    };
+
+    let pattern = match VanityPattern::new(
+        req.prefix.as_deref().unwrap_or(""),
+        req.suffix.as_deref().unwrap_or(""),
+        req.case_sensitive.unwrap_or(true),
+    ) {
+        Ok(pattern) => pattern,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let run_async = req.run_async.unwrap_or(false);
+    let max_timeout = if run_async {
+        MAX_VANITY_JOB_TIMEOUT_SECS
+    } else {
+        MAX_VANITY_TIMEOUT_SECS
+    };
+
+    let timeout_secs = req.timeout_secs.unwrap_or(DEFAULT_VANITY_TIMEOUT_SECS);
+    if timeout_secs == 0 || timeout_secs > max_timeout {
+        return ResponseJson(ApiResponse::error(format!(
+            "Invalid timeoutSecs: must be between 1 and {}",
+            max_timeout
+        )));
+    }
+    let timeout = Duration::from_secs(timeout_secs);
+
+    if run_async {
+        return match state.vanity.clone().start(pattern, timeout).await {
+            Ok(job) => ResponseJson(ApiResponse::success(vanity_response(job))),
+            Err(e) => ResponseJson(ApiResponse::error(e)),
+        };
+    }
+
+    let progress = Arc::new(SearchProgress::default());
+    let started_at = Instant::now();
+
+    let keypair = match state
+        .vanity
+        .search(pattern.clone(), timeout, progress.clone())
+        .await
+    {
+        Ok(Some(keypair)) => keypair,
+        Ok(None) => {
+            return ResponseJson(ApiResponse::error(format!(
+                "Vanity search timed out after {} attempts",
+                progress.attempts.load(Ordering::Relaxed)
+            )));
+        }
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let response = VanityResponse {
+        id: None,
+        status: VanityStatus::Found,
+        estimated_attempts: pattern.estimated_attempts(),
+        prefix: pattern.prefix,
+        suffix: pattern.suffix,
+        case_sensitive: pattern.case_sensitive,
+        attempts: progress.attempts.load(Ordering::Relaxed),
+        elapsed_ms: started_at.elapsed().as_millis(),
+        timeout_ms: timeout.as_millis(),
+        pubkey: Some(keypair.pubkey().to_string()),
+        secret: Some(bs58::encode(keypair.to_bytes()).into_string()),
+    };
+
+    ResponseJson(ApiResponse::success(response))
+}
+
+pub async fn handle_vanity_job_get(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ResponseJson<ApiResponse<VanityResponse>> {
+    println!("🔥 VANITY JOB GET endpoint called with: {}", id);
+
+    match state.vanity.get(&id).await {
+        Some(job) => ResponseJson(ApiResponse::success(vanity_response(job))),
+        None => ResponseJson(ApiResponse::error("Vanity job not found".to_string())),
+    }
+}
+
+pub async fn handle_vanity_job_cancel(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ResponseJson<ApiResponse<VanityResponse>> {
+    println!("🔥 VANITY JOB CANCEL endpoint called with: {}", id);
+
+    match state.vanity.cancel(&id).await {
+        Some(job) => ResponseJson(ApiResponse::success(vanity_response(job))),
+        None => ResponseJson(ApiResponse::error("Vanity job not found".to_string())),
+    }
+}
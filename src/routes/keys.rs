@@ -0,0 +1,356 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::Json as ResponseJson,
+};
+use serde::Deserialize;
+use solana_sdk::signature::Keypair;
+
+use crate::{
+    keystore::{AuditEntry, StoredKey},
+    policy::SigningPolicy,
+    routes::approvals::approval_response,
+    state::AppState,
+    types::{
+        request::{Principal, SafeJson},
+        response::{ApiResponse, KeyResponse, KeyRotateResponse},
+    },
+    utils::parse_secret_key,
+};
+
+const DEFAULT_AUDIT_LIMIT: usize = 100;
+const MAX_AUDIT_LIMIT: usize = 1000;
+
+#[derive(Deserialize, Debug)]
+pub struct KeyCreateRequest {
+    pub label: Option<String>,
+    pub password: Option<String>,
+    pub secret: Option<String>,
+    #[serde(rename = "secretFormat")]
+    pub secret_format: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct KeyUpdateRequest {
+    pub label: Option<String>,
+    pub disabled: Option<bool>,
+    pub password: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct KeyRotateRequest {
+    pub password: Option<String>,
+    #[serde(rename = "newPassword")]
+    pub new_password: Option<String>,
+}
+
+// The policy fields sit at the top level, next to the key's password.
+#[derive(Deserialize)]
+pub struct KeyPolicyRequest {
+    pub password: Option<String>,
+    #[serde(flatten)]
+    pub policy: SigningPolicy,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct KeyAuthRequest {
+    pub password: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct KeyAuditQuery {
+    #[serde(rename = "keyId")]
+    pub key_id: Option<String>,
+    pub limit: Option<String>,
+}
+
+pub fn key_response(stored: StoredKey) -> KeyResponse {
+    KeyResponse {
+        id: stored.id,
+        label: stored.label,
+        pubkey: stored.pubkey,
+        protection: stored.protection,
+        created_at: stored.created_at,
+        last_used_at: stored.last_used_at,
+        disabled: stored.disabled,
+        rotated_from: stored.rotated_from,
+        rotated_to: stored.rotated_to,
+        policy: stored.policy,
+        daily_spend: stored.daily_spend,
+        approval: None,
+    }
+}
+
+pub async fn handle_key_create(
+    State(state): State<AppState>,
+    Principal(principal): Principal,
+    SafeJson(payload): SafeJson<KeyCreateRequest>,
+) -> ResponseJson<ApiResponse<KeyResponse>> {
+    println!("🔥 KEY CREATE endpoint called");
+
+    let req = match payload {
+        Some(req) => req,
+        None => {
+            return ResponseJson(ApiResponse::error("Missing required fields".to_string()));
+        }
+    };
+
+    let (keypair, imported) = match req.secret {
+        Some(secret) if !secret.trim().is_empty() => {
+            match parse_secret_key(&secret, req.secret_format.as_deref()) {
+                Ok(kp) => (kp, true),
+                Err(e) => return ResponseJson(ApiResponse::error(e)),
+            }
+        }
+        _ => (Keypair::new(), false),
+    };
+
+    match state
+        .keystore
+        .create(keypair, req.label, req.password, imported, principal.as_deref())
+        .await
+    {
+        Ok(stored) => ResponseJson(ApiResponse::success(key_response(stored))),
+        Err(e) => ResponseJson(ApiResponse::error(e)),
+    }
+}
+
+pub async fn handle_key_list(
+    State(state): State<AppState>,
+) -> ResponseJson<ApiResponse<Vec<KeyResponse>>> {
+    println!("🔥 KEY LIST endpoint called");
+
+    let keys = state
+        .keystore
+        .list()
+        .await
+        .into_iter()
+        .map(key_response)
+        .collect();
+
+    ResponseJson(ApiResponse::success(keys))
+}
+
+pub async fn handle_key_get(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ResponseJson<ApiResponse<KeyResponse>> {
+    println!("🔥 KEY GET endpoint called with: {}", id);
+
+    match state.keystore.resolve(&id).await {
+        Some(stored) => ResponseJson(ApiResponse::success(key_response(stored))),
+        None => ResponseJson(ApiResponse::error("Key not found".to_string())),
+    }
+}
+
+pub async fn handle_key_update(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Principal(principal): Principal,
+    SafeJson(payload): SafeJson<KeyUpdateRequest>,
+) -> ResponseJson<ApiResponse<KeyResponse>> {
+    println!(
+        "🔥 KEY UPDATE endpoint called with: {} {:?}",
+        id,
+        payload.as_ref().map(|req| (&req.label, req.disabled))
+    );
+
+    let req = match payload {
+        Some(req) => req,
+        None => {
+            return ResponseJson(ApiResponse::error("Missing required fields".to_string()));
+        }
+    };
+
+    if req.label.is_none() && req.disabled.is_none() {
+        return ResponseJson(ApiResponse::error("Missing required fields".to_string()));
+    }
+
+    match state
+        .keystore
+        .update(&id, req.label, req.disabled, req.password, principal.as_deref())
+        .await
+    {
+        Ok(stored) => ResponseJson(ApiResponse::success(key_response(stored))),
+        Err(e) => ResponseJson(ApiResponse::error(e)),
+    }
+}
+
+pub async fn handle_key_rotate(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Principal(principal): Principal,
+    SafeJson(payload): SafeJson<KeyRotateRequest>,
+) -> ResponseJson<ApiResponse<KeyRotateResponse>> {
+    println!("🔥 KEY ROTATE endpoint called with: {}", id);
+
+    let (password, new_password) = match payload {
+        Some(req) => (req.password, req.new_password),
+        None => (None, None),
+    };
+
+    match state
+        .keystore
+        .rotate(&id, password, new_password, principal.as_deref())
+        .await
+    {
+        Ok((retired, replacement)) => ResponseJson(ApiResponse::success(KeyRotateResponse {
+            retired: key_response(retired),
+            replacement: key_response(replacement),
+        })),
+        Err(e) => ResponseJson(ApiResponse::error(e)),
+    }
+}
+
+pub async fn handle_key_policy_set(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Principal(principal): Principal,
+    SafeJson(payload): SafeJson<KeyPolicyRequest>,
+) -> ResponseJson<ApiResponse<KeyResponse>> {
+    println!(
+        "🔥 KEY POLICY SET endpoint called with: {} {:?} {:?}",
+        id,
+        principal,
+        payload.as_ref().map(|req| &req.policy)
+    );
+
+    let req = match payload {
+        Some(req) => req,
+        None => {
+            return ResponseJson(ApiResponse::error("Missing required fields".to_string()));
+        }
+    };
+
+    if let Err(e) = req.policy.validate() {
+        return ResponseJson(ApiResponse::error(e));
+    }
+
+    change_policy(&state, &id, Some(req.policy), req.password, principal).await
+}
+
+pub async fn handle_key_policy_clear(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Principal(principal): Principal,
+    SafeJson(payload): SafeJson<KeyAuthRequest>,
+) -> ResponseJson<ApiResponse<KeyResponse>> {
+    println!("🔥 KEY POLICY CLEAR endpoint called with: {} {:?}", id, principal);
+
+    let password = payload.and_then(|req| req.password);
+    change_policy(&state, &id, None, password, principal).await
+}
+
+// Tightening applies right away. Loosening or clearing a policy is parked
+// for the same approval quorum that guards the key's transactions.
+async fn change_policy(
+    state: &AppState,
+    key_ref: &str,
+    policy: Option<SigningPolicy>,
+    password: Option<String>,
+    principal: Option<String>,
+) -> ResponseJson<ApiResponse<KeyResponse>> {
+    let stored = match state.keystore.resolve(key_ref).await {
+        Some(stored) => stored,
+        None => return ResponseJson(ApiResponse::error("Key not found".to_string())),
+    };
+
+    if let Err(e) = state
+        .keystore
+        .authenticate(&stored, password, principal.as_deref())
+        .await
+    {
+        return ResponseJson(ApiResponse::error(e));
+    }
+
+    let Some(current) = stored.policy.clone().filter(|c| c.is_loosened_by(policy.as_ref())) else {
+        return match state
+            .keystore
+            .set_policy(&stored.id, policy, principal.as_deref())
+            .await
+        {
+            Ok(stored) => ResponseJson(ApiResponse::success(key_response(stored))),
+            Err(e) => ResponseJson(ApiResponse::error(e)),
+        };
+    };
+
+    let quorum = current.quorum();
+    let eligible = state
+        .api_keys
+        .principal_count()
+        .saturating_sub(usize::from(principal.is_some()));
+    if eligible < quorum as usize {
+        return ResponseJson(ApiResponse::error(format!(
+            "Policy change requires {} approvals but only {} approvers are configured",
+            quorum, eligible
+        )));
+    }
+
+    let (request, created) = state
+        .approvals
+        .submit_policy(&stored, quorum, policy, principal)
+        .await;
+
+    if created {
+        state.keystore.record_event(
+            &stored,
+            "approval_requested",
+            request.requested_by.as_deref(),
+            Some(format!("approval {}", request.id)),
+        );
+    }
+
+    let mut response = key_response(stored);
+    response.approval = Some(approval_response(&request));
+    ResponseJson(ApiResponse::success(response))
+}
+
+pub async fn handle_key_delete(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Principal(principal): Principal,
+    SafeJson(payload): SafeJson<KeyAuthRequest>,
+) -> ResponseJson<ApiResponse<KeyResponse>> {
+    println!("🔥 KEY DELETE endpoint called with: {} {:?}", id, principal);
+
+    let password = payload.and_then(|req| req.password);
+
+    match state
+        .keystore
+        .delete(&id, password, principal.as_deref())
+        .await
+    {
+        Ok(stored) => ResponseJson(ApiResponse::success(key_response(stored))),
+        Err(e) => ResponseJson(ApiResponse::error(e)),
+    }
+}
+
+pub async fn handle_key_audit(
+    State(state): State<AppState>,
+    Query(query): Query<KeyAuditQuery>,
+) -> ResponseJson<ApiResponse<Vec<AuditEntry>>> {
+    println!("🔥 KEY AUDIT endpoint called with: {:?}", query);
+
+    let limit = match query.limit.as_deref().map(|l| l.trim().parse::<usize>()) {
+        None => DEFAULT_AUDIT_LIMIT,
+        Some(Ok(val)) if val > 0 && val <= MAX_AUDIT_LIMIT => val,
+        Some(_) => {
+            return ResponseJson(ApiResponse::error(format!(
+                "Invalid limit: must be between 1 and {}",
+                MAX_AUDIT_LIMIT
+            )));
+        }
+    };
+
+    let key_id = match query.key_id.as_deref().map(str::trim) {
+        Some(key_ref) if !key_ref.is_empty() => match state.keystore.resolve(key_ref).await {
+            Some(stored) => Some(stored.id),
+            None => Some(key_ref.to_string()),
+        },
+        _ => None,
+    };
+
+    match state.keystore.audit_trail(key_id.as_deref(), limit) {
+        Ok(entries) => ResponseJson(ApiResponse::success(entries)),
+        Err(e) => ResponseJson(ApiResponse::error(e)),
+    }
+}
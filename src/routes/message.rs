@@ -1,20 +1,40 @@
-use axum::response::Json as ResponseJson;
-use base64::{engine::general_purpose, Engine as _};
+use axum::{extract::State, response::Json as ResponseJson};
 use serde::Deserialize;
-use solana_sdk::{signature::{Signature, Signer}};
+use solana_sdk::signature::Signer;
 
 use crate::{
+    policy::SigningRequest,
+    state::AppState,
     types::{
-        request::{SafeJson, get_required_string},
-        response::{ApiResponse, MessageSignResponse, MessageVerifyResponse},
+        request::{Principal, SafeJson, get_required_string},
+        response::{
+            ApiResponse, MessageSignResponse, MessageVerifyBatchItem, MessageVerifyBatchResponse,
+            MessageVerifyResponse,
+        },
     },
-    utils::{validate_pubkey, parse_secret_key},
+    utils::{
+        decode_message, encode_bytes, parse_signature, signable_message, signature_encoding,
+        validate_pubkey,
+    },
+    verify::{SignedMessage, verify_all},
 };
 
+const MAX_BATCH_ITEMS: usize = 10_000;
+
 #[derive(Deserialize, Debug)]
 pub struct MessageSignRequest {
     pub message: Option<String>,
     pub secret: Option<String>,
+    #[serde(rename = "secretFormat")]
+    pub secret_format: Option<String>,
+    #[serde(rename = "keyId")]
+    pub key_id: Option<String>,
+    pub password: Option<String>,
+    pub format: Option<String>,
+    #[serde(rename = "messageEncoding")]
+    pub message_encoding: Option<String>,
+    #[serde(rename = "signatureEncoding")]
+    pub signature_encoding: Option<String>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -22,12 +42,23 @@ pub struct MessageVerifyRequest {
     pub message: Option<String>,
     pub signature: Option<String>,
     pub pubkey: Option<String>,
+    pub format: Option<String>,
+    #[serde(rename = "messageEncoding")]
+    pub message_encoding: Option<String>,
+    #[serde(rename = "signatureEncoding")]
+    pub signature_encoding: Option<String>,
+}
+
+fn format_name(offchain_format: Option<&str>) -> &'static str {
+    if offchain_format.is_some() { "offchain" } else { "raw" }
 }
 
 pub async fn handle_message_signing(
+    State(state): State<AppState>,
+    Principal(principal): Principal,
     SafeJson(payload): SafeJson<MessageSignRequest>,
 ) -> ResponseJson<ApiResponse<MessageSignResponse>> {
-    println!("🔥 MESSAGE SIGN endpoint called with: {:?}", payload);
+    println!("🔥 MESSAGE SIGN endpoint called");
 
     let req = match payload {
         Some(req) => req,
@@ -41,32 +72,97 @@ pub async fn handle_message_signing(
         _ => return ResponseJson(ApiResponse::error("Missing required fields".to_string())),
     };
 
-    let secret = match get_required_string(req.secret, "secret") {
-        Ok(val) => val,
-        Err(e) => return ResponseJson(ApiResponse::error(e)),
-    };
-
     if message.len() > 1_000_000 {
         return ResponseJson(ApiResponse::error("Message too long".to_string()));
     }
 
-    let keypair = match parse_secret_key(&secret) {
+    let payload = match decode_message(&message, req.message_encoding.as_deref()) {
+        Ok(bytes) => bytes,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let (message_bytes, offchain_format) = match signable_message(&payload, req.format.as_deref()) {
+        Ok(signable) => signable,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let signature_encoding = match signature_encoding(req.signature_encoding.as_deref()) {
+        Ok(encoding) => encoding,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let keypair = match state
+        .keystore
+        .signer(
+            req.secret,
+            req.secret_format.as_deref(),
+            req.key_id,
+            req.password,
+            principal.as_deref(),
+            SigningRequest::Message(&message_bytes),
+        )
+        .await
+    {
         Ok(kp) => kp,
         Err(e) => return ResponseJson(ApiResponse::error(e)),
     };
 
-    let message_bytes = message.as_bytes();
-    let signature = keypair.sign_message(message_bytes);
+    let signature = keypair.sign_message(&message_bytes);
+
+    let encoded_signature = match encode_bytes(signature.as_ref(), &signature_encoding) {
+        Ok(encoded) => encoded,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
 
     let response = MessageSignResponse {
-        signature: general_purpose::STANDARD.encode(signature.as_ref()),
+        signature: encoded_signature,
         public_key: keypair.pubkey().to_string(),
         message,
+        format: format_name(offchain_format).to_string(),
+        offchain_format: offchain_format.map(str::to_string),
     };
 
     ResponseJson(ApiResponse::success(response))
 }
 
+// Decoded form of a verify request, shared by the single and batch routes.
+struct PreparedVerification {
+    message: String,
+    pubkey_str: String,
+    signed: SignedMessage,
+    offchain_format: Option<&'static str>,
+}
+
+fn prepare_verification(req: MessageVerifyRequest) -> Result<PreparedVerification, String> {
+    let message = match req.message {
+        Some(val) if !val.is_empty() => val,
+        _ => return Err("Missing required fields".to_string()),
+    };
+
+    let signature_str = get_required_string(req.signature, "signature")?;
+    let pubkey_str = get_required_string(req.pubkey, "pubkey")?;
+
+    if message.len() > 1_000_000 {
+        return Err("Message too long".to_string());
+    }
+
+    let payload = decode_message(&message, req.message_encoding.as_deref())?;
+    let (message_bytes, offchain_format) = signable_message(&payload, req.format.as_deref())?;
+    let pubkey = validate_pubkey(&pubkey_str)?;
+    let signature = parse_signature(&signature_str, req.signature_encoding.as_deref())?;
+
+    Ok(PreparedVerification {
+        message,
+        pubkey_str,
+        signed: SignedMessage {
+            pubkey,
+            signature,
+            message: message_bytes,
+        },
+        offchain_format,
+    })
+}
+
 pub async fn handle_message_verification(
     SafeJson(payload): SafeJson<MessageVerifyRequest>,
 ) -> ResponseJson<ApiResponse<MessageVerifyResponse>> {
@@ -79,56 +175,83 @@ pub async fn handle_message_verification(
         }
     };
 
-    let message = match req.message {
-        Some(val) if !val.is_empty() => val,
-        _ => return ResponseJson(ApiResponse::error("Missing required fields".to_string())),
-    };
-
-    let signature_str = match get_required_string(req.signature, "signature") {
-        Ok(val) => val,
+    let prepared = match prepare_verification(req) {
+        Ok(prepared) => prepared,
         Err(e) => return ResponseJson(ApiResponse::error(e)),
     };
 
-    let pubkey_str = match get_required_string(req.pubkey, "pubkey") {
-        Ok(val) => val,
-        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    let response = MessageVerifyResponse {
+        valid: prepared.signed.verify(),
+        message: prepared.message,
+        pubkey: prepared.pubkey_str,
+        format: format_name(prepared.offchain_format).to_string(),
+        offchain_format: prepared.offchain_format.map(str::to_string),
     };
 
-    if message.len() > 1_000_000 {
-        return ResponseJson(ApiResponse::error("Message too long".to_string()));
-    }
+    ResponseJson(ApiResponse::success(response))
+}
 
-    let pubkey = match validate_pubkey(&pubkey_str) {
-        Ok(pk) => pk,
-        Err(e) => return ResponseJson(ApiResponse::error(e)),
-    };
+pub async fn handle_message_verify_batch(
+    SafeJson(payload): SafeJson<Vec<MessageVerifyRequest>>,
+) -> ResponseJson<ApiResponse<MessageVerifyBatchResponse>> {
+    println!(
+        "🔥 MESSAGE VERIFY BATCH endpoint called with: {} items",
+        payload.as_ref().map_or(0, Vec::len)
+    );
 
-    let signature_bytes = match general_purpose::STANDARD.decode(&signature_str) {
-        Ok(bytes) => bytes,
-        Err(_) => {
-            return ResponseJson(ApiResponse::error("Invalid signature: Invalid base64 encoding".to_string()));
-        }
+    let items = match payload {
+        Some(items) if !items.is_empty() => items,
+        _ => return ResponseJson(ApiResponse::error("Missing required fields".to_string())),
     };
 
-    if signature_bytes.len() != 64 {
-        return ResponseJson(ApiResponse::error("Invalid signature: Invalid signature length".to_string()));
+    if items.len() > MAX_BATCH_ITEMS {
+        return ResponseJson(ApiResponse::error(format!(
+            "Too many items: at most {} per batch",
+            MAX_BATCH_ITEMS
+        )));
     }
 
-    let signature = match <[u8; 64]>::try_from(signature_bytes) {
-        Ok(sig_array) => Signature::from(sig_array),
-        Err(_) => {
-            return ResponseJson(ApiResponse::error("Invalid signature: Invalid signature format".to_string()));
+    let total = items.len();
+    let mut results: Vec<MessageVerifyBatchItem> = Vec::with_capacity(total);
+    let mut indices = Vec::new();
+    let mut signed = Vec::new();
+
+    for (index, item) in items.into_iter().enumerate() {
+        match prepare_verification(item) {
+            Ok(prepared) => {
+                indices.push(index);
+                signed.push(prepared.signed);
+                results.push(MessageVerifyBatchItem {
+                    index,
+                    valid: false,
+                    error: None,
+                });
+            }
+            Err(e) => results.push(MessageVerifyBatchItem {
+                index,
+                valid: false,
+                error: Some(e),
+            }),
         }
+    }
+
+    let verdicts = match tokio::task::spawn_blocking(move || verify_all(&signed)).await {
+        Ok(verdicts) => verdicts,
+        Err(e) => return ResponseJson(ApiResponse::error(e.to_string())),
     };
 
-    let message_bytes = message.as_bytes();
-    let is_valid = signature.verify(&pubkey.to_bytes(), message_bytes);
+    for (index, valid) in indices.into_iter().zip(verdicts) {
+        results[index].valid = valid;
+    }
 
-    let response = MessageVerifyResponse {
-        valid: is_valid,
-        message,
-        pubkey: pubkey_str,
-    };
+    let valid = results.iter().filter(|r| r.valid).count();
+    let failed = results.iter().filter(|r| r.error.is_some()).count();
 
-    ResponseJson(ApiResponse::success(response))
+    ResponseJson(ApiResponse::success(MessageVerifyBatchResponse {
+        total,
+        valid,
+        invalid: total - valid - failed,
+        failed,
+        results,
+    }))
 }
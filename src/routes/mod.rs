@@ -2,3 +2,13 @@ pub mod message;
 pub mod send;
 pub mod token;
 pub mod keypair;
+pub mod instruction;
+pub mod transaction;
+pub mod account;
+pub mod airdrop;
+pub mod cluster;
+pub mod ws;
+pub mod watch;
+pub mod keys;
+pub mod approvals;
+pub mod auth;
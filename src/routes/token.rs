@@ -1,11 +1,19 @@
-use axum::response::Json as ResponseJson;
+use axum::{
+    extract::{Path, State},
+    response::Json as ResponseJson,
+};
+use heck::ToLowerCamelCase;
 use serde::Deserialize;
+use solana_sdk::{program_option::COption, program_pack::Pack, pubkey::Pubkey};
 use spl_token::instruction::{initialize_mint, mint_to};
+use spl_token_2022::extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions};
 
 use crate::{
+    rpc::fetch_account,
+    state::AppState,
     types::{
-        request::{SafeJson, get_required_string, get_required_u64, get_required_u8},
-        response::{ApiResponse, InstructionResponse},
+        request::{ClusterSelection, SafeJson, get_required_string, get_required_u64, get_required_u8},
+        response::{ApiResponse, InstructionResponse, MintStateResponse, TokenAccountStateResponse},
     },
     utils::{validate_pubkey, convert_instruction_to_response, validate_amount, validate_decimals},
 };
@@ -154,3 +162,168 @@ pub async fn handle_token_minting(
     let response = convert_instruction_to_response(instruction);
     ResponseJson(ApiResponse::success(response))
 }
+
+fn optional_pubkey(value: COption<Pubkey>) -> Option<String> {
+    Option::<Pubkey>::from(value).map(|pk| pk.to_string())
+}
+
+fn extension_names(types: Vec<ExtensionType>) -> Vec<String> {
+    types
+        .iter()
+        .map(|ext| format!("{:?}", ext).to_lower_camel_case())
+        .collect()
+}
+
+fn account_state_name(state: spl_token_2022::state::AccountState) -> String {
+    match state {
+        spl_token_2022::state::AccountState::Uninitialized => "uninitialized",
+        spl_token_2022::state::AccountState::Initialized => "initialized",
+        spl_token_2022::state::AccountState::Frozen => "frozen",
+    }
+    .to_string()
+}
+
+pub async fn handle_mint_state(
+    State(state): State<AppState>,
+    ClusterSelection(cluster): ClusterSelection,
+    Path(address): Path<String>,
+) -> ResponseJson<ApiResponse<MintStateResponse>> {
+    println!("🔥 TOKEN MINT STATE endpoint called with: {}", address);
+
+    let mint_pk = match validate_pubkey(&address) {
+        Ok(pk) => pk,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let cluster = match state.clusters.resolve(cluster.as_deref()) {
+        Ok(cluster) => cluster,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let account = match fetch_account(&cluster.client, &mint_pk).await {
+        Ok(account) => account,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let response = if account.owner == spl_token::id() {
+        let mint = match spl_token::state::Mint::unpack(&account.data) {
+            Ok(mint) => mint,
+            Err(_) => return ResponseJson(ApiResponse::error("Account is not a valid mint".to_string())),
+        };
+
+        MintStateResponse {
+            address: mint_pk.to_string(),
+            program_id: account.owner.to_string(),
+            supply: mint.supply,
+            decimals: mint.decimals,
+            is_initialized: mint.is_initialized,
+            mint_authority: optional_pubkey(mint.mint_authority),
+            freeze_authority: optional_pubkey(mint.freeze_authority),
+            extensions: Vec::new(),
+        }
+    } else if account.owner == spl_token_2022::id() {
+        let state = match StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&account.data) {
+            Ok(state) => state,
+            Err(_) => return ResponseJson(ApiResponse::error("Account is not a valid mint".to_string())),
+        };
+        let extensions = match state.get_extension_types() {
+            Ok(types) => extension_names(types),
+            Err(_) => return ResponseJson(ApiResponse::error("Account is not a valid mint".to_string())),
+        };
+        let mint = state.base;
+
+        MintStateResponse {
+            address: mint_pk.to_string(),
+            program_id: account.owner.to_string(),
+            supply: mint.supply,
+            decimals: mint.decimals,
+            is_initialized: mint.is_initialized,
+            mint_authority: optional_pubkey(mint.mint_authority),
+            freeze_authority: optional_pubkey(mint.freeze_authority),
+            extensions,
+        }
+    } else {
+        return ResponseJson(ApiResponse::error("Account is not owned by a token program".to_string()));
+    };
+
+    ResponseJson(ApiResponse::success(response))
+}
+
+pub async fn handle_token_account_state(
+    State(state): State<AppState>,
+    ClusterSelection(cluster): ClusterSelection,
+    Path(address): Path<String>,
+) -> ResponseJson<ApiResponse<TokenAccountStateResponse>> {
+    println!("🔥 TOKEN ACCOUNT STATE endpoint called with: {}", address);
+
+    let account_pk = match validate_pubkey(&address) {
+        Ok(pk) => pk,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let cluster = match state.clusters.resolve(cluster.as_deref()) {
+        Ok(cluster) => cluster,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let account = match fetch_account(&cluster.client, &account_pk).await {
+        Ok(account) => account,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    // spl-token and Token-2022 share the base account layout, so the legacy
+    // account is unpacked with spl-token and mapped onto the same fields.
+    let (token_account, extensions) = if account.owner == spl_token::id() {
+        let legacy = match spl_token::state::Account::unpack(&account.data) {
+            Ok(acc) => acc,
+            Err(_) => return ResponseJson(ApiResponse::error("Account is not a valid token account".to_string())),
+        };
+        let state = match legacy.state {
+            spl_token::state::AccountState::Uninitialized => spl_token_2022::state::AccountState::Uninitialized,
+            spl_token::state::AccountState::Initialized => spl_token_2022::state::AccountState::Initialized,
+            spl_token::state::AccountState::Frozen => spl_token_2022::state::AccountState::Frozen,
+        };
+        let converted = spl_token_2022::state::Account {
+            mint: legacy.mint,
+            owner: legacy.owner,
+            amount: legacy.amount,
+            delegate: legacy.delegate,
+            state,
+            is_native: legacy.is_native,
+            delegated_amount: legacy.delegated_amount,
+            close_authority: legacy.close_authority,
+        };
+        (converted, Vec::new())
+    } else if account.owner == spl_token_2022::id() {
+        let state = match StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data) {
+            Ok(state) => state,
+            Err(_) => return ResponseJson(ApiResponse::error("Account is not a valid token account".to_string())),
+        };
+        let extensions = match state.get_extension_types() {
+            Ok(types) => extension_names(types),
+            Err(_) => return ResponseJson(ApiResponse::error("Account is not a valid token account".to_string())),
+        };
+        (state.base, extensions)
+    } else {
+        return ResponseJson(ApiResponse::error("Account is not owned by a token program".to_string()));
+    };
+
+    let rent_exempt_reserve: Option<u64> = token_account.is_native.into();
+
+    let response = TokenAccountStateResponse {
+        address: account_pk.to_string(),
+        program_id: account.owner.to_string(),
+        mint: token_account.mint.to_string(),
+        owner: token_account.owner.to_string(),
+        amount: token_account.amount,
+        delegate: optional_pubkey(token_account.delegate),
+        delegated_amount: token_account.delegated_amount,
+        state: account_state_name(token_account.state),
+        is_native: rent_exempt_reserve.is_some(),
+        rent_exempt_reserve,
+        close_authority: optional_pubkey(token_account.close_authority),
+        extensions,
+    };
+
+    ResponseJson(ApiResponse::success(response))
+}
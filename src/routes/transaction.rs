@@ -0,0 +1,743 @@
+use axum::{
+    extract::{Path, State},
+    response::Json as ResponseJson,
+};
+use serde::Deserialize;
+use solana_sdk::{
+    message::{Message, VersionedMessage},
+    packet::PACKET_DATA_SIZE,
+    signature::{Keypair, Signature, Signer},
+    transaction::{Transaction, VersionedTransaction},
+};
+use solana_client::rpc_config::RpcSendTransactionConfig;
+use std::str::FromStr;
+use base64::{engine::general_purpose, Engine as _};
+
+use crate::{
+    approvals::approval_expiry,
+    decoder::{
+        confirmed_transaction_account_keys, decode_confirmed_transaction,
+        decode_message_instructions, message_account_keys, token_balance_changes,
+    },
+    fees::estimate_fee_breakdown,
+    keystore::ApprovalRequirement,
+    policy::SigningRequest,
+    routes::approvals::approval_response,
+    rpc::{confirmation_status_name, fetch_transaction, rpc_error},
+    state::AppState,
+    tracker::TrackedStatus,
+    types::{
+        request::{ClusterSelection, InstructionInput, Principal, SafeJson, get_required_string},
+        response::{
+            AddressTableLookupResponse, ApiResponse, BalanceChangeResponse, FeeEstimateResponse,
+            MessageHeaderResponse, TransactionBuildResponse, TransactionDecodeResponse,
+            TransactionDetailsResponse, TransactionSendResponse, TransactionSignResponse,
+            TransactionStatusResponse,
+        },
+    },
+    utils::{convert_input_to_instruction, decode_bytes, validate_pubkey},
+};
+
+#[derive(Deserialize, Debug)]
+pub struct TransactionDecodeRequest {
+    pub transaction: Option<String>,
+    pub encoding: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct TransactionBuildRequest {
+    #[serde(rename = "feePayer")]
+    pub fee_payer: Option<String>,
+    pub instructions: Option<Vec<InstructionInput>>,
+    pub cluster: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct TransactionFeeRequest {
+    pub transaction: Option<String>,
+    pub encoding: Option<String>,
+    #[serde(rename = "feePayer")]
+    pub fee_payer: Option<String>,
+    pub instructions: Option<Vec<InstructionInput>>,
+    pub cluster: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct TransactionSignRequest {
+    pub transaction: Option<String>,
+    pub encoding: Option<String>,
+    pub secret: Option<String>,
+    #[serde(rename = "secretFormat")]
+    pub secret_format: Option<String>,
+    #[serde(rename = "keyId")]
+    pub key_id: Option<String>,
+    pub password: Option<String>,
+    pub cluster: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct TransactionSendRequest {
+    pub transaction: Option<String>,
+    pub encoding: Option<String>,
+    #[serde(rename = "lastValidBlockHeight")]
+    pub last_valid_block_height: Option<u64>,
+    pub cluster: Option<String>,
+}
+
+pub async fn handle_transaction_decode(
+    SafeJson(payload): SafeJson<TransactionDecodeRequest>,
+) -> ResponseJson<ApiResponse<TransactionDecodeResponse>> {
+    println!("🔥 TRANSACTION DECODE endpoint called with: {:?}", payload);
+
+    let req = match payload {
+        Some(req) => req,
+        None => {
+            return ResponseJson(ApiResponse::error("Missing required fields".to_string()));
+        }
+    };
+
+    let transaction_str = match get_required_string(req.transaction, "transaction") {
+        Ok(val) => val,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let encoding = req.encoding.unwrap_or_else(|| "base64".to_string());
+
+    let bytes = match decode_bytes(&transaction_str, &encoding) {
+        Ok(bytes) => bytes,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let transaction: VersionedTransaction = match bincode::deserialize(&bytes) {
+        Ok(tx) => tx,
+        Err(_) => {
+            return ResponseJson(ApiResponse::error("Invalid transaction".to_string()));
+        }
+    };
+
+    if transaction.sanitize().is_err() {
+        return ResponseJson(ApiResponse::error("Invalid transaction".to_string()));
+    }
+
+    let message = &transaction.message;
+    let header = message.header();
+
+    let version = match message {
+        VersionedMessage::Legacy(_) => "legacy".to_string(),
+        VersionedMessage::V0(_) => "0".to_string(),
+    };
+
+    let address_table_lookups = message
+        .address_table_lookups()
+        .unwrap_or_default()
+        .iter()
+        .map(|lookup| AddressTableLookupResponse {
+            account_key: lookup.account_key.to_string(),
+            writable_indexes: lookup.writable_indexes.clone(),
+            readonly_indexes: lookup.readonly_indexes.clone(),
+        })
+        .collect();
+
+    let response = TransactionDecodeResponse {
+        version,
+        signatures: transaction
+            .signatures
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+        header: MessageHeaderResponse {
+            num_required_signatures: header.num_required_signatures,
+            num_readonly_signed_accounts: header.num_readonly_signed_accounts,
+            num_readonly_unsigned_accounts: header.num_readonly_unsigned_accounts,
+        },
+        account_keys: message_account_keys(message, None),
+        recent_blockhash: message.recent_blockhash().to_string(),
+        address_table_lookups,
+        instructions: decode_message_instructions(message, None),
+    };
+
+    ResponseJson(ApiResponse::success(response))
+}
+
+pub async fn handle_transaction_details(
+    State(state): State<AppState>,
+    ClusterSelection(cluster): ClusterSelection,
+    Path(signature): Path<String>,
+) -> ResponseJson<ApiResponse<TransactionDetailsResponse>> {
+    println!("🔥 TRANSACTION DETAILS endpoint called with: {}", signature);
+
+    let signature = match Signature::from_str(signature.trim()) {
+        Ok(sig) => sig,
+        Err(_) => return ResponseJson(ApiResponse::error("Invalid signature".to_string())),
+    };
+
+    let cluster = match state.clusters.resolve(cluster.as_deref()) {
+        Ok(cluster) => cluster,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let client = &cluster.client;
+
+    let tx = match fetch_transaction(client, &signature).await {
+        Ok(tx) => tx,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let confirmation_status = match client.get_signature_statuses_with_history(&[signature]).await {
+        Ok(response) => response
+            .value
+            .into_iter()
+            .flatten()
+            .next()
+            .and_then(|status| status.confirmation_status)
+            .map(|status| confirmation_status_name(&status)),
+        Err(e) => return ResponseJson(ApiResponse::error(rpc_error(e))),
+    };
+
+    let meta = match tx.transaction.meta.clone() {
+        Some(meta) => meta,
+        None => return ResponseJson(ApiResponse::error("Transaction status unavailable".to_string())),
+    };
+
+    let instructions = decode_confirmed_transaction(&tx);
+
+    let account_keys = confirmed_transaction_account_keys(&tx);
+
+    let balances = meta
+        .pre_balances
+        .iter()
+        .zip(meta.post_balances.iter())
+        .enumerate()
+        .map(|(i, (&pre, &post))| BalanceChangeResponse {
+            pubkey: account_keys.get(i).cloned().unwrap_or_default(),
+            pre,
+            post,
+            change: post as i64 - pre as i64,
+        })
+        .collect();
+
+    let token_balances = token_balance_changes(
+        &account_keys,
+        Option::from(meta.pre_token_balances).unwrap_or_default(),
+        Option::from(meta.post_token_balances).unwrap_or_default(),
+    );
+
+    let response = TransactionDetailsResponse {
+        signature: signature.to_string(),
+        slot: tx.slot,
+        block_time: tx.block_time,
+        status: if meta.err.is_none() { "success" } else { "failed" }.to_string(),
+        confirmation_status,
+        fee: meta.fee,
+        error: meta.err.map(|e| e.to_string()),
+        logs: Option::from(meta.log_messages).unwrap_or_default(),
+        balances,
+        token_balances,
+        instructions,
+    };
+
+    ResponseJson(ApiResponse::success(response))
+}
+
+pub async fn handle_transaction_build(
+    State(state): State<AppState>,
+    ClusterSelection(cluster): ClusterSelection,
+    SafeJson(payload): SafeJson<TransactionBuildRequest>,
+) -> ResponseJson<ApiResponse<TransactionBuildResponse>> {
+    println!("🔥 TRANSACTION BUILD endpoint called with: {:?}", payload);
+
+    let req = match payload {
+        Some(req) => req,
+        None => {
+            return ResponseJson(ApiResponse::error("Missing required fields".to_string()));
+        }
+    };
+
+    let fee_payer_str = match get_required_string(req.fee_payer, "feePayer") {
+        Ok(val) => val,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let fee_payer = match validate_pubkey(&fee_payer_str) {
+        Ok(pk) => pk,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let instructions = match req.instructions {
+        Some(ixs) if !ixs.is_empty() => ixs,
+        _ => return ResponseJson(ApiResponse::error("Missing required fields".to_string())),
+    };
+
+    let instructions = match instructions
+        .iter()
+        .map(|ix| convert_input_to_instruction(ix, &fee_payer))
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(ixs) => ixs,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let cluster = match state.clusters.resolve(req.cluster.as_deref().or(cluster.as_deref())) {
+        Ok(cluster) => cluster,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let latest = match state.blockhash.latest(cluster).await {
+        Ok(latest) => latest,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let message = Message::new_with_blockhash(&instructions, Some(&fee_payer), &latest.blockhash);
+    let signers = message.account_keys[..message.header.num_required_signatures as usize]
+        .iter()
+        .map(|key| key.to_string())
+        .collect();
+    let message_bytes = message.serialize();
+    let transaction = Transaction::new_unsigned(message);
+
+    let transaction_bytes = match bincode::serialize(&transaction) {
+        Ok(bytes) => bytes,
+        Err(_) => {
+            return ResponseJson(ApiResponse::error("Failed to serialize transaction".to_string()));
+        }
+    };
+
+    if transaction_bytes.len() > PACKET_DATA_SIZE {
+        return ResponseJson(ApiResponse::error(
+            "Transaction too large: exceeds 1232 bytes".to_string(),
+        ));
+    }
+
+    ResponseJson(ApiResponse::success(TransactionBuildResponse {
+        transaction: general_purpose::STANDARD.encode(&transaction_bytes),
+        message: general_purpose::STANDARD.encode(&message_bytes),
+        recent_blockhash: latest.blockhash.to_string(),
+        last_valid_block_height: latest.last_valid_block_height,
+        signers,
+    }))
+}
+
+pub async fn handle_transaction_fee(
+    State(state): State<AppState>,
+    ClusterSelection(cluster): ClusterSelection,
+    SafeJson(payload): SafeJson<TransactionFeeRequest>,
+) -> ResponseJson<ApiResponse<FeeEstimateResponse>> {
+    println!("🔥 TRANSACTION FEE endpoint called with: {:?}", payload);
+
+    let req = match payload {
+        Some(req) => req,
+        None => {
+            return ResponseJson(ApiResponse::error("Missing required fields".to_string()));
+        }
+    };
+
+    let cluster = match state.clusters.resolve(req.cluster.as_deref().or(cluster.as_deref())) {
+        Ok(cluster) => cluster,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let latest = match state.blockhash.latest(cluster).await {
+        Ok(latest) => latest,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    // A pre-built transaction may carry an expired blockhash, which
+    // `getFeeForMessage` rejects, so the cached one is swapped in either way.
+    let mut message = match (req.transaction, req.instructions) {
+        (Some(transaction_str), _) if !transaction_str.trim().is_empty() => {
+            let encoding = req.encoding.unwrap_or_else(|| "base64".to_string());
+
+            let bytes = match decode_bytes(&transaction_str, &encoding) {
+                Ok(bytes) => bytes,
+                Err(e) => return ResponseJson(ApiResponse::error(e)),
+            };
+
+            match bincode::deserialize::<VersionedTransaction>(&bytes) {
+                Ok(tx) if tx.sanitize().is_ok() => tx.message,
+                _ => return ResponseJson(ApiResponse::error("Invalid transaction".to_string())),
+            }
+        }
+        (_, Some(instructions)) if !instructions.is_empty() => {
+            let fee_payer_str = match get_required_string(req.fee_payer, "feePayer") {
+                Ok(val) => val,
+                Err(e) => return ResponseJson(ApiResponse::error(e)),
+            };
+
+            let fee_payer = match validate_pubkey(&fee_payer_str) {
+                Ok(pk) => pk,
+                Err(e) => return ResponseJson(ApiResponse::error(e)),
+            };
+
+            let instructions = match instructions
+                .iter()
+                .map(|ix| convert_input_to_instruction(ix, &fee_payer))
+                .collect::<Result<Vec<_>, _>>()
+            {
+                Ok(ixs) => ixs,
+                Err(e) => return ResponseJson(ApiResponse::error(e)),
+            };
+
+            VersionedMessage::Legacy(Message::new(&instructions, Some(&fee_payer)))
+        }
+        _ => return ResponseJson(ApiResponse::error("Missing required fields".to_string())),
+    };
+
+    message.set_recent_blockhash(latest.blockhash);
+
+    let fee = match &message {
+        VersionedMessage::Legacy(legacy) => cluster.client.get_fee_for_message(legacy).await,
+        VersionedMessage::V0(v0) => cluster.client.get_fee_for_message(v0).await,
+    };
+
+    let fee = match fee {
+        Ok(fee) => fee,
+        Err(e) => return ResponseJson(ApiResponse::error(rpc_error(e))),
+    };
+
+    let breakdown = match estimate_fee_breakdown(&cluster.client, &message).await {
+        Ok(breakdown) => breakdown,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let fee_payer = message.static_account_keys()[0].to_string();
+
+    let rent_total: u64 = breakdown
+        .rent_deposits
+        .iter()
+        .filter(|d| d.funder == fee_payer)
+        .map(|d| d.lamports)
+        .sum();
+
+    ResponseJson(ApiResponse::success(FeeEstimateResponse {
+        fee,
+        rent_total,
+        transfer_total: breakdown.transfer_total,
+        total: fee
+            .saturating_add(rent_total)
+            .saturating_add(breakdown.transfer_total),
+        rent_deposits: breakdown.rent_deposits,
+        fee_payer,
+    }))
+}
+
+pub async fn handle_transaction_sign(
+    State(state): State<AppState>,
+    ClusterSelection(cluster): ClusterSelection,
+    Principal(principal): Principal,
+    SafeJson(payload): SafeJson<TransactionSignRequest>,
+) -> ResponseJson<ApiResponse<TransactionSignResponse>> {
+    println!("🔥 TRANSACTION SIGN endpoint called");
+
+    let req = match payload {
+        Some(req) => req,
+        None => {
+            return ResponseJson(ApiResponse::error("Missing required fields".to_string()));
+        }
+    };
+
+    let transaction_str = match get_required_string(req.transaction, "transaction") {
+        Ok(val) => val,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let encoding = req.encoding.unwrap_or_else(|| "base64".to_string());
+
+    let bytes = match decode_bytes(&transaction_str, &encoding) {
+        Ok(bytes) => bytes,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let mut transaction: VersionedTransaction = match bincode::deserialize(&bytes) {
+        Ok(tx) => tx,
+        Err(_) => {
+            return ResponseJson(ApiResponse::error("Invalid transaction".to_string()));
+        }
+    };
+
+    if transaction.sanitize().is_err() {
+        return ResponseJson(ApiResponse::error("Invalid transaction".to_string()));
+    }
+
+    // Stored keys whose policy asks for approval park the transaction instead
+    // of signing it; it is signed later through `/approvals/:id/execute`.
+    if let Some(key_id) = req.key_id.as_deref().filter(|k| !k.trim().is_empty())
+        && req.secret.as_deref().is_none_or(|s| s.trim().is_empty())
+    {
+        let requirement = match state
+            .keystore
+            .approval_requirement(key_id.trim(), &transaction.message)
+            .await
+        {
+            Ok(requirement) => requirement,
+            Err(e) => return ResponseJson(ApiResponse::error(e)),
+        };
+
+        if let Some(requirement) = requirement {
+            return park_for_approval(&state, cluster, req.cluster, principal, transaction, requirement)
+                .await;
+        }
+    }
+
+    let keypair = match state
+        .keystore
+        .signer(
+            req.secret,
+            req.secret_format.as_deref(),
+            req.key_id,
+            req.password,
+            principal.as_deref(),
+            SigningRequest::Transaction(&transaction.message),
+        )
+        .await
+    {
+        Ok(kp) => kp,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    match sign_transaction(&mut transaction, &keypair) {
+        Ok(response) => ResponseJson(ApiResponse::success(response)),
+        Err(e) => ResponseJson(ApiResponse::error(e)),
+    }
+}
+
+async fn park_for_approval(
+    state: &AppState,
+    header_cluster: Option<String>,
+    body_cluster: Option<String>,
+    principal: Option<String>,
+    transaction: VersionedTransaction,
+    requirement: ApprovalRequirement,
+) -> ResponseJson<ApiResponse<TransactionSignResponse>> {
+    let cluster = match state
+        .clusters
+        .resolve(body_cluster.as_deref().or(header_cluster.as_deref()))
+    {
+        Ok(cluster) => cluster,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let eligible = state
+        .api_keys
+        .principal_count()
+        .saturating_sub(usize::from(principal.is_some()));
+    if eligible < requirement.quorum as usize {
+        return ResponseJson(ApiResponse::error(format!(
+            "Transaction requires {} approvals but only {} approvers are configured",
+            requirement.quorum, eligible
+        )));
+    }
+
+    let expiry = match approval_expiry(cluster, &state.blockhash, &transaction).await {
+        Ok(expiry) => expiry,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let stored = requirement.stored.clone();
+    let (request, created) = state
+        .approvals
+        .submit(requirement, &cluster.name, transaction, principal, expiry)
+        .await;
+
+    if created {
+        state.keystore.record_event(
+            &stored,
+            "approval_requested",
+            request.requested_by.as_deref(),
+            Some(format!("approval {}", request.id)),
+        );
+    }
+
+    let approval = approval_response(&request);
+    ResponseJson(ApiResponse::success(TransactionSignResponse {
+        transaction: approval.transaction.clone().unwrap_or_default(),
+        signature: None,
+        signer: request.pubkey.clone(),
+        complete: false,
+        approval: Some(approval),
+    }))
+}
+
+// Adds `keypair`'s signature in its slot among the required signers.
+pub fn sign_transaction(
+    transaction: &mut VersionedTransaction,
+    keypair: &Keypair,
+) -> Result<TransactionSignResponse, String> {
+    let signer = keypair.pubkey();
+    let required = transaction.message.header().num_required_signatures as usize;
+
+    let index = transaction.message.static_account_keys()[..required]
+        .iter()
+        .position(|key| *key == signer)
+        .ok_or_else(|| {
+            format!(
+                "Invalid signer: {} is not a required signer of this transaction",
+                signer
+            )
+        })?;
+
+    let signature = keypair.sign_message(&transaction.message.serialize());
+    transaction.signatures[index] = signature;
+
+    let transaction_bytes =
+        bincode::serialize(transaction).map_err(|_| "Failed to serialize transaction".to_string())?;
+
+    Ok(TransactionSignResponse {
+        transaction: general_purpose::STANDARD.encode(transaction_bytes),
+        signature: Some(signature.to_string()),
+        signer: signer.to_string(),
+        complete: transaction.verify_with_results().iter().all(|ok| *ok),
+        approval: None,
+    })
+}
+
+pub async fn handle_transaction_send(
+    State(state): State<AppState>,
+    ClusterSelection(cluster): ClusterSelection,
+    SafeJson(payload): SafeJson<TransactionSendRequest>,
+) -> ResponseJson<ApiResponse<TransactionSendResponse>> {
+    println!("🔥 TRANSACTION SEND endpoint called with: {:?}", payload);
+
+    let req = match payload {
+        Some(req) => req,
+        None => {
+            return ResponseJson(ApiResponse::error("Missing required fields".to_string()));
+        }
+    };
+
+    let transaction_str = match get_required_string(req.transaction, "transaction") {
+        Ok(val) => val,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let encoding = req.encoding.unwrap_or_else(|| "base64".to_string());
+
+    let bytes = match decode_bytes(&transaction_str, &encoding) {
+        Ok(bytes) => bytes,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let transaction: VersionedTransaction = match bincode::deserialize(&bytes) {
+        Ok(tx) => tx,
+        Err(_) => {
+            return ResponseJson(ApiResponse::error("Invalid transaction".to_string()));
+        }
+    };
+
+    if transaction.sanitize().is_err() {
+        return ResponseJson(ApiResponse::error("Invalid transaction".to_string()));
+    }
+
+    if transaction.verify_with_results().iter().any(|ok| !ok) {
+        return ResponseJson(ApiResponse::error(
+            "Invalid transaction: missing or invalid signatures".to_string(),
+        ));
+    }
+
+    let cluster = match state.clusters.resolve(req.cluster.as_deref().or(cluster.as_deref())) {
+        Ok(cluster) => cluster,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let last_valid_block_height = match req.last_valid_block_height {
+        Some(height) => height,
+        None => match state
+            .blockhash
+            .last_valid_block_height(cluster, transaction.message.recent_blockhash())
+            .await
+        {
+            Ok(height) => height,
+            Err(e) => return ResponseJson(ApiResponse::error(e)),
+        },
+    };
+
+    let config = RpcSendTransactionConfig {
+        preflight_commitment: Some(cluster.commitment.commitment),
+        max_retries: Some(0),
+        ..RpcSendTransactionConfig::default()
+    };
+
+    let signature = match cluster
+        .client
+        .send_transaction_with_config(&transaction, config)
+        .await
+    {
+        Ok(sig) => sig,
+        Err(e) => return ResponseJson(ApiResponse::error(rpc_error(e))),
+    };
+
+    state
+        .tracker
+        .clone()
+        .track(
+            cluster.name.clone(),
+            cluster.client.clone(),
+            transaction,
+            last_valid_block_height,
+        )
+        .await;
+
+    ResponseJson(ApiResponse::success(TransactionSendResponse {
+        signature: signature.to_string(),
+        cluster: cluster.name.clone(),
+        last_valid_block_height,
+        status: TrackedStatus::Pending,
+    }))
+}
+
+pub async fn handle_transaction_status(
+    State(state): State<AppState>,
+    ClusterSelection(cluster): ClusterSelection,
+    Path(signature): Path<String>,
+) -> ResponseJson<ApiResponse<TransactionStatusResponse>> {
+    println!("🔥 TRANSACTION STATUS endpoint called with: {}", signature);
+
+    let signature = match Signature::from_str(signature.trim()) {
+        Ok(sig) => sig,
+        Err(_) => return ResponseJson(ApiResponse::error("Invalid signature".to_string())),
+    };
+
+    if let Some(tracked) = state.tracker.get(&signature).await {
+        return ResponseJson(ApiResponse::success(TransactionStatusResponse {
+            signature: signature.to_string(),
+            status: tracked.status,
+            tracked: true,
+            cluster: tracked.cluster,
+            slot: tracked.slot,
+            error: tracked.error,
+            last_valid_block_height: Some(tracked.last_valid_block_height),
+            rebroadcasts: tracked.rebroadcasts,
+            elapsed_ms: Some(tracked.submitted_at.elapsed().as_millis()),
+        }));
+    }
+
+    let cluster = match state.clusters.resolve(cluster.as_deref()) {
+        Ok(cluster) => cluster,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let status = match cluster
+        .client
+        .get_signature_statuses_with_history(&[signature])
+        .await
+    {
+        Ok(response) => response.value.into_iter().flatten().next(),
+        Err(e) => return ResponseJson(ApiResponse::error(rpc_error(e))),
+    };
+
+    let status = match status {
+        Some(status) => status,
+        None => return ResponseJson(ApiResponse::error("Transaction not found".to_string())),
+    };
+
+    ResponseJson(ApiResponse::success(TransactionStatusResponse {
+        signature: signature.to_string(),
+        status: TrackedStatus::from_signature_status(&status),
+        tracked: false,
+        cluster: cluster.name.clone(),
+        slot: Some(status.slot),
+        error: status.err.map(|e| e.to_string()),
+        last_valid_block_height: None,
+        rebroadcasts: 0,
+        elapsed_ms: None,
+    }))
+}
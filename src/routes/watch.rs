@@ -0,0 +1,295 @@
+use axum::{
+    extract::{Path, State},
+    response::Json as ResponseJson,
+};
+use serde::Deserialize;
+use std::net::IpAddr;
+
+use crate::{
+    routes::approvals::required_principal,
+    state::AppState,
+    types::{
+        request::{ClusterSelection, Principal, SafeJson, get_required_string},
+        response::{ApiResponse, WatchResponse},
+    },
+    utils::{random_hex, unix_now, validate_account_pubkey},
+    watch::Watch,
+};
+
+#[derive(Deserialize, Debug)]
+pub struct WatchCreateRequest {
+    pub address: Option<String>,
+    #[serde(rename = "callbackUrl")]
+    pub callback_url: Option<String>,
+    pub secret: Option<String>,
+    pub cluster: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct WatchUpdateRequest {
+    #[serde(rename = "callbackUrl")]
+    pub callback_url: Option<String>,
+    pub active: Option<bool>,
+}
+
+fn watch_response(watch: Watch, include_secret: bool) -> WatchResponse {
+    WatchResponse {
+        id: watch.id,
+        address: watch.address.to_string(),
+        callback_url: watch.callback_url,
+        cluster: watch.cluster,
+        active: watch.active,
+        created_at: watch.created_at,
+        last_signature: watch.last_signature,
+        deliveries: watch.deliveries,
+        failed_deliveries: watch.failed_deliveries,
+        last_error: watch.last_error,
+        secret: include_secret.then_some(watch.secret),
+    }
+}
+
+// Webhooks are sent from the server, so a callback may not point back at it
+// or at anything else on its private network.
+fn is_internal_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_internal_address(IpAddr::V4(ip)),
+            None => {
+                ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_unique_local()
+                    || ip.is_unicast_link_local()
+            }
+        },
+    }
+}
+
+async fn validate_callback_url(url: &str) -> Result<String, String> {
+    let trimmed = url.trim();
+    if !(trimmed.starts_with("http://") || trimmed.starts_with("https://")) {
+        return Err("Invalid callback URL".to_string());
+    }
+    let parsed = reqwest::Url::parse(trimmed).map_err(|_| "Invalid callback URL".to_string())?;
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| "Invalid callback URL".to_string())?
+        .trim_start_matches('[')
+        .trim_end_matches(']');
+
+    let addresses: Vec<IpAddr> = match host.parse::<IpAddr>() {
+        Ok(ip) => vec![ip],
+        Err(_) => {
+            let port = parsed.port_or_known_default().unwrap_or(80);
+            tokio::net::lookup_host((host, port))
+                .await
+                .map_err(|_| "Invalid callback URL: host does not resolve".to_string())?
+                .map(|addr| addr.ip())
+                .collect()
+        }
+    };
+
+    if addresses.into_iter().any(is_internal_address) {
+        return Err("Invalid callback URL: internal addresses are not allowed".to_string());
+    }
+    Ok(trimmed.to_string())
+}
+
+pub async fn handle_watch_create(
+    State(state): State<AppState>,
+    ClusterSelection(cluster): ClusterSelection,
+    Principal(principal): Principal,
+    SafeJson(payload): SafeJson<WatchCreateRequest>,
+) -> ResponseJson<ApiResponse<WatchResponse>> {
+    // The webhook secret is deliberately left out of the log.
+    println!(
+        "🔥 WATCH CREATE endpoint called with: {:?} {:?}",
+        principal,
+        payload.as_ref().map(|req| (&req.address, &req.callback_url))
+    );
+
+    if let Err(e) = required_principal(principal) {
+        return ResponseJson(ApiResponse::error(e));
+    }
+
+    let req = match payload {
+        Some(req) => req,
+        None => {
+            return ResponseJson(ApiResponse::error("Missing required fields".to_string()));
+        }
+    };
+
+    let address_str = match get_required_string(req.address, "address") {
+        Ok(val) => val,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let callback_url = match get_required_string(req.callback_url, "callbackUrl") {
+        Ok(val) => val,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let address = match validate_account_pubkey(&address_str) {
+        Ok(pk) => pk,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let callback_url = match validate_callback_url(&callback_url).await {
+        Ok(url) => url,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let cluster = match state.clusters.resolve(req.cluster.as_deref().or(cluster.as_deref())) {
+        Ok(cluster) => cluster,
+        Err(e) => return ResponseJson(ApiResponse::error(e)),
+    };
+
+    let secret = match req.secret {
+        Some(secret) if !secret.trim().is_empty() => secret.trim().to_string(),
+        _ => random_hex(32),
+    };
+
+    let watch = Watch {
+        id: random_hex(16),
+        address,
+        callback_url,
+        secret,
+        cluster: cluster.name.clone(),
+        active: true,
+        created_at: unix_now(),
+        last_signature: None,
+        deliveries: 0,
+        failed_deliveries: 0,
+        last_error: None,
+    };
+
+    if let Err(e) = state.watches.insert(watch.clone()).await {
+        return ResponseJson(ApiResponse::error(e));
+    }
+
+    ResponseJson(ApiResponse::success(watch_response(watch, true)))
+}
+
+pub async fn handle_watch_list(
+    State(state): State<AppState>,
+) -> ResponseJson<ApiResponse<Vec<WatchResponse>>> {
+    println!("🔥 WATCH LIST endpoint called");
+
+    let watches = state
+        .watches
+        .list()
+        .await
+        .into_iter()
+        .map(|watch| watch_response(watch, false))
+        .collect();
+
+    ResponseJson(ApiResponse::success(watches))
+}
+
+pub async fn handle_watch_get(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ResponseJson<ApiResponse<WatchResponse>> {
+    println!("🔥 WATCH GET endpoint called with: {}", id);
+
+    match state.watches.get(&id).await {
+        Some(watch) => ResponseJson(ApiResponse::success(watch_response(watch, false))),
+        None => ResponseJson(ApiResponse::error("Watch not found".to_string())),
+    }
+}
+
+pub async fn handle_watch_update(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    SafeJson(payload): SafeJson<WatchUpdateRequest>,
+) -> ResponseJson<ApiResponse<WatchResponse>> {
+    println!("🔥 WATCH UPDATE endpoint called with: {} {:?}", id, payload);
+
+    let req = match payload {
+        Some(req) => req,
+        None => {
+            return ResponseJson(ApiResponse::error("Missing required fields".to_string()));
+        }
+    };
+
+    let callback_url = match req.callback_url {
+        Some(url) => match validate_callback_url(&url).await {
+            Ok(url) => Some(url),
+            Err(e) => return ResponseJson(ApiResponse::error(e)),
+        },
+        None => None,
+    };
+
+    let updated = state
+        .watches
+        .update(&id, |watch| {
+            if let Some(url) = callback_url {
+                watch.callback_url = url;
+            }
+            if let Some(active) = req.active {
+                watch.active = active;
+            }
+        })
+        .await;
+
+    match updated {
+        Some(watch) => ResponseJson(ApiResponse::success(watch_response(watch, false))),
+        None => ResponseJson(ApiResponse::error("Watch not found".to_string())),
+    }
+}
+
+pub async fn handle_watch_delete(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ResponseJson<ApiResponse<WatchResponse>> {
+    println!("🔥 WATCH DELETE endpoint called with: {}", id);
+
+    match state.watches.remove(&id).await {
+        Some(watch) => ResponseJson(ApiResponse::success(watch_response(watch, false))),
+        None => ResponseJson(ApiResponse::error("Watch not found".to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn callbacks_to_internal_hosts_are_rejected() {
+        for url in [
+            "http://127.0.0.1:8080/hook",
+            "http://localhost/hook",
+            "http://10.1.2.3/hook",
+            "https://192.168.0.10/hook",
+            "http://172.16.0.1/hook",
+            "http://169.254.169.254/latest/meta-data",
+            "http://0.0.0.0/hook",
+            "http://[::1]/hook",
+            "http://[fd00::1]/hook",
+            "http://[fe80::1]/hook",
+            "http://[::ffff:127.0.0.1]/hook",
+        ] {
+            assert_eq!(
+                validate_callback_url(url).await.err().unwrap(),
+                "Invalid callback URL: internal addresses are not allowed",
+                "{}",
+                url
+            );
+        }
+
+        assert_eq!(
+            validate_callback_url("ftp://example.com/hook").await.err().unwrap(),
+            "Invalid callback URL"
+        );
+        assert_eq!(
+            validate_callback_url(" https://8.8.8.8/hook ").await.unwrap(),
+            "https://8.8.8.8/hook"
+        );
+    }
+}
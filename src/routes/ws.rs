@@ -0,0 +1,227 @@
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    response::Response,
+};
+use futures::{SinkExt, StreamExt};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use solana_sdk::signature::Signature;
+use std::{collections::HashMap, str::FromStr};
+use tokio::{
+    sync::{
+        broadcast::error::RecvError,
+        mpsc::{self, error::TrySendError},
+    },
+    task::JoinHandle,
+};
+
+use crate::{
+    pubsub::{GatewayEvent, Topic},
+    state::AppState,
+    types::request::ClusterSelection,
+    utils::validate_account_pubkey,
+};
+
+// Messages queued for a client that reads slower than notifications arrive.
+// Once full, notifications are dropped and reported with a `lagged` event.
+const OUTGOING_BUFFER: usize = 256;
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "action", rename_all = "lowercase")]
+enum ClientMessage {
+    Subscribe {
+        #[serde(rename = "type")]
+        kind: Option<String>,
+        pubkey: Option<String>,
+        signature: Option<String>,
+        mentions: Option<String>,
+        cluster: Option<String>,
+    },
+    Unsubscribe {
+        id: Option<u64>,
+    },
+}
+
+pub async fn handle_ws(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+    ClusterSelection(cluster): ClusterSelection,
+) -> Response {
+    println!("🔥 WS endpoint called with: {:?}", cluster);
+
+    ws.on_upgrade(move |socket| handle_socket(socket, state, cluster))
+}
+
+fn parse_topic(
+    kind: Option<String>,
+    pubkey: Option<String>,
+    signature: Option<String>,
+    mentions: Option<String>,
+) -> Result<Topic, String> {
+    match kind.as_deref() {
+        Some("account") => {
+            let pubkey = pubkey.ok_or_else(|| "Missing required fields".to_string())?;
+            Ok(Topic::Account(validate_account_pubkey(&pubkey)?))
+        }
+        Some("signature") => {
+            let signature = signature.ok_or_else(|| "Missing required fields".to_string())?;
+            Signature::from_str(signature.trim())
+                .map(Topic::Signature)
+                .map_err(|_| "Invalid signature".to_string())
+        }
+        Some("logs") => match mentions {
+            Some(pubkey) => Ok(Topic::Logs(Some(validate_account_pubkey(&pubkey)?))),
+            None => Ok(Topic::Logs(None)),
+        },
+        Some(_) => Err("Invalid type: must be one of account, signature, logs".to_string()),
+        None => Err("Missing required fields".to_string()),
+    }
+}
+
+// Replies to the client's own requests are never dropped: if the buffer is
+// full the client has stopped reading, and the socket is closed instead.
+fn reply(outgoing: &mpsc::Sender<Value>, message: Value) -> bool {
+    outgoing.try_send(message).is_ok()
+}
+
+async fn handle_socket(socket: WebSocket, state: AppState, default_cluster: Option<String>) {
+    let (mut sink, mut incoming) = socket.split();
+    let (outgoing, mut outgoing_rx) = mpsc::channel::<Value>(OUTGOING_BUFFER);
+
+    let writer = tokio::spawn(async move {
+        while let Some(message) = outgoing_rx.recv().await {
+            if sink.send(Message::Text(message.to_string())).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut subscriptions: HashMap<u64, JoinHandle<()>> = HashMap::new();
+    let mut next_id: u64 = 1;
+
+    while let Some(Ok(message)) = incoming.next().await {
+        let text = match message {
+            Message::Text(text) => text,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+
+        let request = match serde_json::from_str::<ClientMessage>(&text) {
+            Ok(request) => request,
+            Err(_) => {
+                if !reply(&outgoing, json!({ "event": "error", "error": "Invalid message" })) {
+                    break;
+                }
+                continue;
+            }
+        };
+
+        match request {
+            ClientMessage::Subscribe {
+                kind,
+                pubkey,
+                signature,
+                mentions,
+                cluster,
+            } => {
+                let topic = match parse_topic(kind.clone(), pubkey, signature, mentions) {
+                    Ok(topic) => topic,
+                    Err(e) => {
+                        if !reply(&outgoing, json!({ "event": "error", "error": e })) {
+                            break;
+                        }
+                        continue;
+                    }
+                };
+
+                let cluster = match state
+                    .clusters
+                    .resolve(cluster.as_deref().or(default_cluster.as_deref()))
+                {
+                    Ok(cluster) => cluster,
+                    Err(e) => {
+                        if !reply(&outgoing, json!({ "event": "error", "error": e })) {
+                            break;
+                        }
+                        continue;
+                    }
+                };
+
+                let id = next_id;
+                next_id += 1;
+
+                let mut receiver = state.pubsub.subscribe(cluster, topic);
+                let forward_to = outgoing.clone();
+
+                let handle = tokio::spawn(async move {
+                    let mut dropped: u64 = 0;
+
+                    loop {
+                        match receiver.recv().await {
+                            Ok(GatewayEvent::Notification(result)) => {
+                                if dropped > 0 {
+                                    let lagged = json!({ "event": "lagged", "id": id, "skipped": dropped });
+                                    match forward_to.try_send(lagged) {
+                                        Ok(()) => dropped = 0,
+                                        Err(TrySendError::Full(_)) => {
+                                            dropped += 1;
+                                            continue;
+                                        }
+                                        Err(TrySendError::Closed(_)) => break,
+                                    }
+                                }
+
+                                let event = json!({ "event": "notification", "id": id, "result": result });
+                                match forward_to.try_send(event) {
+                                    Ok(()) => {}
+                                    Err(TrySendError::Full(_)) => dropped += 1,
+                                    Err(TrySendError::Closed(_)) => break,
+                                }
+                            }
+                            Ok(GatewayEvent::Closed(reason)) => {
+                                let _ = forward_to
+                                    .send(json!({ "event": "closed", "id": id, "reason": reason }))
+                                    .await;
+                                break;
+                            }
+                            Err(RecvError::Lagged(skipped)) => dropped += skipped,
+                            Err(RecvError::Closed) => break,
+                        }
+                    }
+                });
+
+                subscriptions.insert(id, handle);
+                let subscribed = json!({
+                    "event": "subscribed",
+                    "id": id,
+                    "type": kind,
+                    "cluster": cluster.name,
+                });
+                if !reply(&outgoing, subscribed) {
+                    break;
+                }
+            }
+            ClientMessage::Unsubscribe { id } => {
+                let response =
+                    match id.and_then(|id| subscriptions.remove(&id).map(|handle| (id, handle))) {
+                        Some((id, handle)) => {
+                            handle.abort();
+                            json!({ "event": "unsubscribed", "id": id })
+                        }
+                        None => json!({ "event": "error", "error": "Unknown subscription" }),
+                    };
+                if !reply(&outgoing, response) {
+                    break;
+                }
+            }
+        }
+    }
+
+    for handle in subscriptions.into_values() {
+        handle.abort();
+    }
+    writer.abort();
+}
@@ -0,0 +1,62 @@
+use serde_json::json;
+use solana_client::{
+    nonblocking::rpc_client::RpcClient, rpc_config::RpcTransactionConfig,
+    rpc_request::RpcRequest,
+};
+use solana_sdk::{
+    account::Account, pubkey::Pubkey, signature::Signature,
+};
+use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta, TransactionConfirmationStatus,
+    UiTransactionEncoding,
+};
+
+pub fn rpc_error(err: impl std::fmt::Display) -> String {
+    format!("RPC request failed: {}", err)
+}
+
+pub async fn fetch_account(client: &RpcClient, pubkey: &Pubkey) -> Result<Account, String> {
+    match client
+        .get_account_with_commitment(pubkey, client.commitment())
+        .await
+    {
+        Ok(response) => response
+            .value
+            .ok_or_else(|| "Account not found".to_string()),
+        Err(e) => Err(rpc_error(e)),
+    }
+}
+
+pub async fn fetch_transaction(
+    client: &RpcClient,
+    signature: &Signature,
+) -> Result<EncodedConfirmedTransactionWithStatusMeta, String> {
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Base64),
+        commitment: Some(client.commitment()),
+        max_supported_transaction_version: Some(0),
+    };
+
+    // `getTransaction` answers `null` for unknown signatures, which the typed
+    // client helper would surface as a deserialization error.
+    match client
+        .send::<Option<EncodedConfirmedTransactionWithStatusMeta>>(
+            RpcRequest::GetTransaction,
+            json!([signature.to_string(), config]),
+        )
+        .await
+    {
+        Ok(Some(tx)) => Ok(tx),
+        Ok(None) => Err("Transaction not found".to_string()),
+        Err(e) => Err(rpc_error(e)),
+    }
+}
+
+pub fn confirmation_status_name(status: &TransactionConfirmationStatus) -> String {
+    match status {
+        TransactionConfirmationStatus::Processed => "processed",
+        TransactionConfirmationStatus::Confirmed => "confirmed",
+        TransactionConfirmationStatus::Finalized => "finalized",
+    }
+    .to_string()
+}
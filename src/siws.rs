@@ -0,0 +1,548 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+use zeroize::Zeroizing;
+
+use crate::utils::unix_now;
+
+pub const SIWS_DOMAINS_ENV: &str = "SIWS_DOMAINS";
+const DEFAULT_DOMAIN: &str = "localhost:3000";
+const HEADER_SUFFIX: &str = " wants you to sign in with your Solana account:";
+const CHALLENGE_TTL_SECS: u64 = 600;
+const SESSION_TTL_SECS: u64 = 86_400;
+const MAX_CLOCK_SKEW_SECS: u64 = 60;
+const NONCE_RANDOM_LEN: usize = 16;
+const NONCE_MAC_LEN: usize = 16;
+const SESSION_MAC_LEN: usize = 32;
+
+// Sign-In With Solana message, laid out as wallets render it:
+//
+//   {domain} wants you to sign in with your Solana account:
+//   {address}
+//
+//   {statement}
+//
+//   URI: ...
+//   Version: 1
+//   ...
+#[derive(Clone, Debug, Default)]
+pub struct SiwsMessage {
+    pub domain: String,
+    pub address: String,
+    pub statement: Option<String>,
+    pub uri: Option<String>,
+    pub version: Option<String>,
+    pub chain_id: Option<String>,
+    pub nonce: Option<String>,
+    pub issued_at: Option<String>,
+    pub expiration_time: Option<String>,
+    pub not_before: Option<String>,
+    pub request_id: Option<String>,
+    pub resources: Vec<String>,
+}
+
+impl SiwsMessage {
+    pub fn render(&self) -> String {
+        let mut out = format!("{}{}\n{}", self.domain, HEADER_SUFFIX, self.address);
+
+        if let Some(statement) = &self.statement {
+            out.push_str("\n\n");
+            out.push_str(statement);
+        }
+
+        let fields = [
+            ("URI", &self.uri),
+            ("Version", &self.version),
+            ("Chain ID", &self.chain_id),
+            ("Nonce", &self.nonce),
+            ("Issued At", &self.issued_at),
+            ("Expiration Time", &self.expiration_time),
+            ("Not Before", &self.not_before),
+            ("Request ID", &self.request_id),
+        ];
+
+        let mut separated = false;
+        for (name, value) in fields {
+            if let Some(value) = value {
+                out.push_str(if separated { "\n" } else { "\n\n" });
+                separated = true;
+                out.push_str(&format!("{}: {}", name, value));
+            }
+        }
+
+        if !self.resources.is_empty() {
+            out.push_str(if separated { "\n" } else { "\n\n" });
+            out.push_str("Resources:");
+            for resource in &self.resources {
+                out.push_str(&format!("\n- {}", resource));
+            }
+        }
+
+        out
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let invalid = |reason: &str| format!("Invalid SIWS message: {}", reason);
+        let mut lines = text.lines();
+
+        let domain = lines
+            .next()
+            .and_then(|line| line.strip_suffix(HEADER_SUFFIX))
+            .filter(|d| !d.is_empty() && !d.contains(char::is_whitespace))
+            .ok_or_else(|| invalid("missing sign-in header"))?;
+
+        let address = lines
+            .next()
+            .map(str::trim)
+            .filter(|a| !a.is_empty())
+            .ok_or_else(|| invalid("missing address"))?;
+
+        let mut message = SiwsMessage {
+            domain: domain.to_string(),
+            address: address.to_string(),
+            ..Self::default()
+        };
+
+        let mut in_fields = false;
+        let mut in_resources = false;
+        let mut statement: Vec<&str> = Vec::new();
+
+        for line in lines {
+            if in_resources {
+                match line.strip_prefix("- ") {
+                    Some(resource) => message.resources.push(resource.to_string()),
+                    None => return Err(invalid("unexpected line after Resources")),
+                }
+                continue;
+            }
+
+            if line == "Resources:" {
+                in_fields = true;
+                in_resources = true;
+                continue;
+            }
+
+            let field = line.split_once(": ").and_then(|(name, value)| {
+                let slot = match name {
+                    "URI" => &mut message.uri,
+                    "Version" => &mut message.version,
+                    "Chain ID" => &mut message.chain_id,
+                    "Nonce" => &mut message.nonce,
+                    "Issued At" => &mut message.issued_at,
+                    "Expiration Time" => &mut message.expiration_time,
+                    "Not Before" => &mut message.not_before,
+                    "Request ID" => &mut message.request_id,
+                    _ => return None,
+                };
+                Some((slot, value))
+            });
+
+            match field {
+                Some((slot, value)) => {
+                    if slot.is_some() {
+                        return Err(invalid("duplicate field"));
+                    }
+                    *slot = Some(value.to_string());
+                    in_fields = true;
+                }
+                None if in_fields => return Err(invalid("unexpected line after fields")),
+                None => statement.push(line),
+            }
+        }
+
+        let statement = statement.join("\n");
+        let statement = statement.trim_matches('\n');
+        if !statement.is_empty() {
+            message.statement = Some(statement.to_string());
+        }
+
+        Ok(message)
+    }
+}
+
+pub fn format_timestamp(secs: u64) -> String {
+    DateTime::<Utc>::from_timestamp(secs as i64, 0)
+        .unwrap_or_default()
+        .to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn parse_timestamp(value: &str, field: &str) -> Result<u64, String> {
+    DateTime::parse_from_rfc3339(value.trim())
+        .ok()
+        .and_then(|t| u64::try_from(t.timestamp()).ok())
+        .ok_or_else(|| format!("Invalid SIWS message: {} is not an RFC 3339 timestamp", field))
+}
+
+#[derive(Clone)]
+pub struct Session {
+    pub address: String,
+    pub domain: String,
+    pub issued_at: u64,
+    pub expires_at: u64,
+}
+
+// Nonces are stateless: each carries its expiry and whether it was bound to
+// an address, authenticated with a per-process key, so issuing challenges
+// stores nothing. Only redeemed nonces are remembered, until they expire, so
+// each is accepted once. Session tokens carry the session itself under a
+// second key, so sign-ins store nothing either.
+pub struct SiwsAuth {
+    domains: Vec<String>,
+    nonce_key: Zeroizing<[u8; 32]>,
+    session_key: Zeroizing<[u8; 32]>,
+    used_nonces: RwLock<HashMap<String, u64>>,
+}
+
+// random || expires_at || address flag
+fn nonce_payload(random: &[u8], expires_at: u64, bound: bool) -> Vec<u8> {
+    let mut payload = random.to_vec();
+    payload.extend_from_slice(&expires_at.to_be_bytes());
+    payload.push(u8::from(bound));
+    payload
+}
+
+// expires_at || issued_at || domain \n address. Neither the domain nor the
+// address can contain whitespace.
+fn session_payload(session: &Session) -> Vec<u8> {
+    let mut payload = session.expires_at.to_be_bytes().to_vec();
+    payload.extend_from_slice(&session.issued_at.to_be_bytes());
+    payload.extend_from_slice(session.domain.as_bytes());
+    payload.push(b'\n');
+    payload.extend_from_slice(session.address.as_bytes());
+    payload
+}
+
+impl SiwsAuth {
+    pub fn load() -> Result<Self, String> {
+        let domains: Vec<String> = match std::env::var(SIWS_DOMAINS_ENV) {
+            Ok(config) => config
+                .split(',')
+                .map(str::trim)
+                .filter(|d| !d.is_empty())
+                .map(str::to_string)
+                .collect(),
+            Err(_) => vec![DEFAULT_DOMAIN.to_string()],
+        };
+
+        if domains.is_empty() {
+            return Err(format!("{} must list at least one domain", SIWS_DOMAINS_ENV));
+        }
+        if let Some(domain) = domains.iter().find(|d| d.contains(char::is_whitespace)) {
+            return Err(format!("Invalid domain in {}: {}", SIWS_DOMAINS_ENV, domain));
+        }
+
+        let mut nonce_key = Zeroizing::new([0u8; 32]);
+        rand::thread_rng().fill_bytes(nonce_key.as_mut());
+        let mut session_key = Zeroizing::new([0u8; 32]);
+        rand::thread_rng().fill_bytes(session_key.as_mut());
+
+        Ok(Self {
+            domains,
+            nonce_key,
+            session_key,
+            used_nonces: RwLock::new(HashMap::new()),
+        })
+    }
+
+    // The MAC also covers the domain and, when bound, the address, so a
+    // nonce cannot be moved to another site or account.
+    fn nonce_mac(&self, payload: &[u8], domain: &str, address: Option<&str>) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.nonce_key.as_ref())
+            .expect("HMAC accepts keys of any length");
+        mac.update(payload);
+        mac.update(domain.as_bytes());
+        mac.update(&[0]);
+        mac.update(address.unwrap_or_default().as_bytes());
+        mac
+    }
+
+    fn issue_nonce(&self, domain: &str, address: Option<&str>, expires_at: u64) -> String {
+        let mut random = [0u8; NONCE_RANDOM_LEN];
+        rand::thread_rng().fill_bytes(&mut random);
+
+        let mut nonce = nonce_payload(&random, expires_at, address.is_some());
+        let mac = self.nonce_mac(&nonce, domain, address).finalize().into_bytes();
+        nonce.extend_from_slice(&mac[..NONCE_MAC_LEN]);
+        hex::encode(nonce)
+    }
+
+    // Returns the nonce's expiry if this server issued it for this domain
+    // and address.
+    fn open_nonce(&self, nonce: &str, domain: &str, address: &str) -> Result<u64, String> {
+        let invalid = || "Invalid nonce: unknown, expired or already used".to_string();
+
+        let bytes = hex::decode(nonce).map_err(|_| invalid())?;
+        if bytes.len() != NONCE_RANDOM_LEN + 8 + 1 + NONCE_MAC_LEN {
+            return Err(invalid());
+        }
+        let (payload, mac) = bytes.split_at(NONCE_RANDOM_LEN + 8 + 1);
+
+        let bound = match payload[payload.len() - 1] {
+            0 => false,
+            1 => true,
+            _ => return Err(invalid()),
+        };
+        self.nonce_mac(payload, domain, bound.then_some(address))
+            .verify_truncated_left(mac)
+            .map_err(|_| invalid())?;
+
+        let expires_at = u64::from_be_bytes(
+            payload[NONCE_RANDOM_LEN..NONCE_RANDOM_LEN + 8]
+                .try_into()
+                .map_err(|_| invalid())?,
+        );
+        if expires_at <= unix_now() {
+            return Err(invalid());
+        }
+        Ok(expires_at)
+    }
+
+    fn session_mac(&self, payload: &[u8]) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.session_key.as_ref())
+            .expect("HMAC accepts keys of any length");
+        mac.update(payload);
+        mac
+    }
+
+    fn issue_session_token(&self, session: &Session) -> String {
+        let mut token = session_payload(session);
+        let mac = self.session_mac(&token).finalize().into_bytes();
+        token.extend_from_slice(&mac);
+        hex::encode(token)
+    }
+
+    pub fn domains(&self) -> &[String] {
+        &self.domains
+    }
+
+    pub async fn challenge(
+        &self,
+        domain: Option<&str>,
+        address: Option<String>,
+        statement: Option<String>,
+        uri: Option<String>,
+        chain_id: Option<String>,
+    ) -> Result<SiwsMessage, String> {
+        let domain = match domain.map(str::trim).filter(|d| !d.is_empty()) {
+            Some(domain) if self.domains.iter().any(|d| d == domain) => domain.to_string(),
+            Some(domain) => return Err(format!("Invalid domain: {}", domain)),
+            None => self.domains[0].clone(),
+        };
+
+        if statement.as_deref().is_some_and(|s| s.contains('\n')) {
+            return Err("Invalid statement: must be a single line".to_string());
+        }
+
+        let now = unix_now();
+        let expires_at = now + CHALLENGE_TTL_SECS;
+        let nonce = self.issue_nonce(&domain, address.as_deref(), expires_at);
+
+        Ok(SiwsMessage {
+            domain,
+            address: address.unwrap_or_default(),
+            statement,
+            uri,
+            version: Some("1".to_string()),
+            chain_id,
+            nonce: Some(nonce),
+            issued_at: Some(format_timestamp(now)),
+            expiration_time: Some(format_timestamp(expires_at)),
+            ..SiwsMessage::default()
+        })
+    }
+
+    // Checks everything except the signature, which the caller verifies
+    // before redeeming. The nonce is only consumed by `redeem`.
+    pub async fn check(&self, message: &SiwsMessage) -> Result<(), String> {
+        let now = unix_now();
+
+        if !self.domains.contains(&message.domain) {
+            return Err(format!("Invalid domain: {}", message.domain));
+        }
+
+        if message.version.as_deref().is_some_and(|v| v != "1") {
+            return Err("Invalid SIWS message: unsupported version".to_string());
+        }
+
+        let nonce = message
+            .nonce
+            .as_deref()
+            .ok_or_else(|| "Invalid SIWS message: missing nonce".to_string())?;
+
+        let issued_at = message
+            .issued_at
+            .as_deref()
+            .ok_or_else(|| "Invalid SIWS message: missing Issued At".to_string())?;
+        if parse_timestamp(issued_at, "Issued At")? > now + MAX_CLOCK_SKEW_SECS {
+            return Err("Invalid SIWS message: issued in the future".to_string());
+        }
+        if let Some(expiration) = message.expiration_time.as_deref()
+            && parse_timestamp(expiration, "Expiration Time")? <= now
+        {
+            return Err("Invalid SIWS message: expired".to_string());
+        }
+        if let Some(not_before) = message.not_before.as_deref()
+            && parse_timestamp(not_before, "Not Before")? > now + MAX_CLOCK_SKEW_SECS
+        {
+            return Err("Invalid SIWS message: not yet valid".to_string());
+        }
+
+        self.open_nonce(nonce, &message.domain, &message.address)?;
+        if self.used_nonces.read().await.contains_key(nonce) {
+            return Err("Invalid nonce: unknown, expired or already used".to_string());
+        }
+
+        Ok(())
+    }
+
+    // Consumes the nonce and opens a session. Two concurrent sign-ins with
+    // the same nonce cannot both succeed.
+    pub async fn redeem(&self, message: &SiwsMessage) -> Result<(String, Session), String> {
+        let nonce = message.nonce.as_deref().unwrap_or_default();
+        let expires_at = self.open_nonce(nonce, &message.domain, &message.address)?;
+
+        let now = unix_now();
+        {
+            let mut used = self.used_nonces.write().await;
+            used.retain(|_, expires_at| *expires_at > now);
+            if used.insert(nonce.to_string(), expires_at).is_some() {
+                return Err("Invalid nonce: unknown, expired or already used".to_string());
+            }
+        }
+
+        let session = Session {
+            address: message.address.clone(),
+            domain: message.domain.clone(),
+            issued_at: now,
+            expires_at: now + SESSION_TTL_SECS,
+        };
+
+        Ok((self.issue_session_token(&session), session))
+    }
+
+    pub fn session(&self, token: &str) -> Option<Session> {
+        let bytes = hex::decode(token.trim()).ok()?;
+        if bytes.len() < 16 + SESSION_MAC_LEN {
+            return None;
+        }
+        let (payload, mac) = bytes.split_at(bytes.len() - SESSION_MAC_LEN);
+        self.session_mac(payload).verify_slice(mac).ok()?;
+
+        let expires_at = u64::from_be_bytes(payload[..8].try_into().ok()?);
+        let issued_at = u64::from_be_bytes(payload[8..16].try_into().ok()?);
+        let (domain, address) = std::str::from_utf8(&payload[16..]).ok()?.split_once('\n')?;
+
+        (expires_at > unix_now()).then(|| Session {
+            address: address.to_string(),
+            domain: domain.to_string(),
+            issued_at,
+            expires_at,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESS: &str = "CWxJ3j2gDtwyakDKzYuUzUJwSTcfY4jAZCho3S28WEoK";
+    const OTHER: &str = "5vfs61Q56e5rfSSHvwB1dcXtA3fh7c5MEcwBiGVV7zQK";
+
+    fn auth() -> SiwsAuth {
+        SiwsAuth {
+            domains: vec!["example.com".to_string(), "other.example".to_string()],
+            ..SiwsAuth::load().unwrap()
+        }
+    }
+
+    #[tokio::test]
+    async fn nonce_is_accepted_once() {
+        let auth = auth();
+        let message = auth
+            .challenge(None, Some(ADDRESS.to_string()), None, None, None)
+            .await
+            .unwrap();
+        let parsed = SiwsMessage::parse(&message.render()).unwrap();
+
+        auth.check(&parsed).await.unwrap();
+        let (_, session) = auth.redeem(&parsed).await.unwrap();
+        assert_eq!(session.address, ADDRESS);
+
+        assert!(auth.check(&parsed).await.is_err());
+        assert!(auth.redeem(&parsed).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn nonce_is_bound_to_domain_and_address() {
+        let auth = auth();
+        let bound = auth
+            .challenge(None, Some(ADDRESS.to_string()), None, None, None)
+            .await
+            .unwrap();
+
+        let mut other_address = bound.clone();
+        other_address.address = OTHER.to_string();
+        assert!(auth.check(&other_address).await.is_err());
+
+        let mut other_domain = bound.clone();
+        other_domain.domain = "other.example".to_string();
+        assert!(auth.check(&other_domain).await.is_err());
+
+        // An unbound challenge can be signed by any address.
+        let mut unbound = auth.challenge(None, None, None, None, None).await.unwrap();
+        unbound.address = OTHER.to_string();
+        auth.check(&unbound).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn forged_or_foreign_nonces_are_rejected() {
+        let auth = auth();
+        let message = auth
+            .challenge(None, Some(ADDRESS.to_string()), None, None, None)
+            .await
+            .unwrap();
+
+        let nonce = message.nonce.clone().unwrap();
+        let mut tampered = message.clone();
+        let last = if nonce.ends_with('0') { "1" } else { "0" };
+        tampered.nonce = Some(format!("{}{}", &nonce[..nonce.len() - 1], last));
+        assert!(auth.check(&tampered).await.is_err());
+
+        let mut garbage = message.clone();
+        garbage.nonce = Some("deadbeef".to_string());
+        assert!(auth.check(&garbage).await.is_err());
+
+        // Another server instance has its own nonce key.
+        assert!(self::auth().check(&message).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn session_tokens_carry_the_session() {
+        let auth = auth();
+        let message = auth
+            .challenge(None, Some(ADDRESS.to_string()), None, None, None)
+            .await
+            .unwrap();
+        let (token, session) = auth.redeem(&message).await.unwrap();
+
+        let restored = auth.session(&format!(" {} ", token)).unwrap();
+        assert_eq!(restored.address, ADDRESS);
+        assert_eq!(restored.domain, "example.com");
+        assert_eq!(restored.issued_at, session.issued_at);
+        assert_eq!(restored.expires_at, session.expires_at);
+
+        let last = if token.ends_with('0') { "1" } else { "0" };
+        assert!(auth.session(&format!("{}{}", &token[..token.len() - 1], last)).is_none());
+        assert!(auth.session("deadbeef").is_none());
+        assert!(self::auth().session(&token).is_none());
+
+        let expired = Session {
+            expires_at: unix_now() - 1,
+            ..session
+        };
+        assert!(auth.session(&auth.issue_session_token(&expired)).is_none());
+    }
+}
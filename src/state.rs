@@ -0,0 +1,54 @@
+use std::sync::Arc;
+
+use crate::{
+    approvals::ApprovalQueue, auth::ApiKeys, blockhash::BlockhashCache,
+    cluster::ClusterRegistry, idl::IdlRegistry, keystore::Keystore, pubsub::PubsubGateway, siws::SiwsAuth,
+    tracker::TransactionTracker, vanity::VanityJobs, watch::WatchRegistry,
+};
+
+#[derive(Clone)]
+pub struct AppState {
+    pub clusters: Arc<ClusterRegistry>,
+    pub blockhash: Arc<BlockhashCache>,
+    pub tracker: Arc<TransactionTracker>,
+    pub pubsub: Arc<PubsubGateway>,
+    pub watches: Arc<WatchRegistry>,
+    pub vanity: Arc<VanityJobs>,
+    pub keystore: Arc<Keystore>,
+    pub api_keys: Arc<ApiKeys>,
+    pub approvals: Arc<ApprovalQueue>,
+    pub siws: Arc<SiwsAuth>,
+    pub idls: Arc<IdlRegistry>,
+}
+
+impl AppState {
+    pub fn new(
+        clusters: ClusterRegistry,
+        keystore: Keystore,
+        api_keys: ApiKeys,
+        siws: SiwsAuth,
+        idls: IdlRegistry,
+    ) -> Self {
+        Self {
+            clusters: Arc::new(clusters),
+            blockhash: Arc::new(BlockhashCache::default()),
+            tracker: Arc::new(TransactionTracker::default()),
+            pubsub: Arc::new(PubsubGateway::default()),
+            watches: Arc::new(WatchRegistry::default()),
+            vanity: Arc::new(VanityJobs::default()),
+            keystore: Arc::new(keystore),
+            api_keys: Arc::new(api_keys),
+            approvals: Arc::new(ApprovalQueue::default()),
+            siws: Arc::new(siws),
+            idls: Arc::new(idls),
+        }
+    }
+
+    pub fn spawn_background_tasks(&self) {
+        self.blockhash.clone().spawn_refresher(self.clusters.clone());
+        self.watches.clone().spawn_monitor(self.clusters.clone());
+        self.approvals
+            .clone()
+            .spawn_monitor(self.clusters.clone(), self.keystore.clone());
+    }
+}
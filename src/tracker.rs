@@ -0,0 +1,208 @@
+use serde::Serialize;
+use solana_client::{
+    client_error::ClientError, nonblocking::rpc_client::RpcClient,
+    rpc_config::RpcSendTransactionConfig,
+};
+use solana_sdk::{signature::Signature, transaction::VersionedTransaction};
+use solana_transaction_status::{TransactionConfirmationStatus, TransactionStatus};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::RwLock;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+const RETENTION: Duration = Duration::from_secs(3600);
+// A blockhash is valid for about 150 blocks (roughly a minute), so this only
+// ends tracking when the RPC node keeps failing.
+const TRACKING_DEADLINE: Duration = Duration::from_secs(300);
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum TrackedStatus {
+    Pending,
+    Confirmed,
+    Finalized,
+    Expired,
+    Failed,
+    Unknown,
+}
+
+impl TrackedStatus {
+    pub fn from_signature_status(status: &TransactionStatus) -> Self {
+        match (&status.err, &status.confirmation_status) {
+            (Some(_), _) => Self::Failed,
+            (None, Some(TransactionConfirmationStatus::Finalized)) => Self::Finalized,
+            (None, Some(TransactionConfirmationStatus::Confirmed)) => Self::Confirmed,
+            _ => Self::Pending,
+        }
+    }
+
+    fn is_terminal(self) -> bool {
+        matches!(
+            self,
+            Self::Finalized | Self::Expired | Self::Failed | Self::Unknown
+        )
+    }
+}
+
+#[derive(Clone)]
+pub struct TrackedTransaction {
+    pub cluster: String,
+    pub last_valid_block_height: u64,
+    pub status: TrackedStatus,
+    pub slot: Option<u64>,
+    pub error: Option<String>,
+    pub rebroadcasts: u32,
+    pub submitted_at: Instant,
+}
+
+#[derive(Default)]
+pub struct TransactionTracker {
+    entries: RwLock<HashMap<Signature, TrackedTransaction>>,
+}
+
+impl TransactionTracker {
+    pub async fn get(&self, signature: &Signature) -> Option<TrackedTransaction> {
+        self.entries.read().await.get(signature).cloned()
+    }
+
+    // Polls the signature until it lands or the blockhash expires, resending
+    // the same bytes each round it is still unknown to the cluster. Finished
+    // entries are kept for an hour so `/status` can still report them.
+    pub async fn track(
+        self: Arc<Self>,
+        cluster: String,
+        client: Arc<RpcClient>,
+        transaction: VersionedTransaction,
+        last_valid_block_height: u64,
+    ) {
+        let signature = transaction.signatures[0];
+
+        self.entries.write().await.insert(
+            signature,
+            TrackedTransaction {
+                cluster,
+                last_valid_block_height,
+                status: TrackedStatus::Pending,
+                slot: None,
+                error: None,
+                rebroadcasts: 0,
+                submitted_at: Instant::now(),
+            },
+        );
+
+        tokio::spawn(async move {
+            let resend_config = RpcSendTransactionConfig {
+                skip_preflight: true,
+                max_retries: Some(0),
+                ..RpcSendTransactionConfig::default()
+            };
+
+            let deadline = Instant::now() + TRACKING_DEADLINE;
+
+            loop {
+                tokio::time::sleep(POLL_INTERVAL).await;
+
+                if Instant::now() >= deadline {
+                    self.update(&signature, |entry| {
+                        if entry.status == TrackedStatus::Pending {
+                            entry.status = TrackedStatus::Unknown;
+                            entry.error =
+                                Some("Gave up tracking: RPC node unavailable".to_string());
+                        }
+                    })
+                    .await;
+                    break;
+                }
+
+                let status = match fetch_status(&client, &signature).await {
+                    Ok(status) => status,
+                    Err(e) => {
+                        println!("⚠️  Status check failed for {}: {}", signature, e);
+                        continue;
+                    }
+                };
+
+                if let Some(status) = status {
+                    if self.record_status(&signature, &status).await.is_terminal() {
+                        break;
+                    }
+                    continue;
+                }
+
+                match client.get_block_height().await {
+                    Ok(height) if height > last_valid_block_height => {
+                        // The transaction may have landed since the status
+                        // check above, so look once more before giving up.
+                        match fetch_status(&client, &signature).await {
+                            Ok(Some(status)) => {
+                                if self.record_status(&signature, &status).await.is_terminal() {
+                                    break;
+                                }
+                                continue;
+                            }
+                            Ok(None) => {
+                                self.update(&signature, |entry| entry.status = TrackedStatus::Expired)
+                                    .await;
+                                break;
+                            }
+                            Err(e) => {
+                                println!("⚠️  Status check failed for {}: {}", signature, e);
+                                continue;
+                            }
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        println!("⚠️  Block height check failed for {}: {}", signature, e);
+                        continue;
+                    }
+                }
+
+                match client
+                    .send_transaction_with_config(&transaction, resend_config)
+                    .await
+                {
+                    Ok(_) => self.update(&signature, |entry| entry.rebroadcasts += 1).await,
+                    Err(e) => println!("⚠️  Rebroadcast failed for {}: {}", signature, e),
+                }
+            }
+
+            tokio::time::sleep(RETENTION).await;
+            self.entries.write().await.remove(&signature);
+        });
+    }
+
+    async fn record_status(
+        &self,
+        signature: &Signature,
+        status: &TransactionStatus,
+    ) -> TrackedStatus {
+        let next = TrackedStatus::from_signature_status(status);
+
+        self.update(signature, |entry| {
+            entry.status = next;
+            entry.slot = Some(status.slot);
+            entry.error = status.err.as_ref().map(|e| e.to_string());
+        })
+        .await;
+
+        next
+    }
+
+    async fn update(&self, signature: &Signature, apply: impl FnOnce(&mut TrackedTransaction)) {
+        if let Some(entry) = self.entries.write().await.get_mut(signature) {
+            apply(entry);
+        }
+    }
+}
+
+async fn fetch_status(
+    client: &RpcClient,
+    signature: &Signature,
+) -> Result<Option<TransactionStatus>, ClientError> {
+    let response = client.get_signature_statuses(&[*signature]).await?;
+    Ok(response.value.into_iter().next().flatten())
+}
@@ -1,11 +1,18 @@
 use axum::{
-    extract::{ FromRequest, Request},
+    extract::{ FromRequest, FromRequestParts, Query, Request},
+    http::request::Parts,
     response::Json as ResponseJson,
 };
-use serde::de::DeserializeOwned;
+use serde::{de::DeserializeOwned, Deserialize};
 use serde_json::Value;
+use std::convert::Infallible;
 
-use crate::types::response::ApiResponse;
+use crate::{
+    auth::API_KEY_HEADER,
+    cluster::CLUSTER_HEADER,
+    state::AppState,
+    types::response::{AccountInfo, ApiResponse},
+};
 
 pub struct SafeJson<T>(pub Option<T>);
 
@@ -17,7 +24,7 @@ where
 {
     type Rejection = ResponseJson<ApiResponse<()>>;
 
-    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+    async fn from_request(req: Request, _state: &S) -> Result<Self, Self::Rejection> {
         let bytes = match axum::body::to_bytes(req.into_body(), usize::MAX).await {
             Ok(bytes) => bytes,
             Err(_) => {
@@ -49,23 +56,113 @@ where
     }
 }
 
-pub fn get_required_string(opt: Option<String>, field_name: &str) -> Result<String, String> {
+pub fn get_required_string(opt: Option<String>, _field_name: &str) -> Result<String, String> {
     match opt {
         Some(val) if !val.trim().is_empty() => Ok(val.trim().to_string()),
         _ => Err("Missing required fields".to_string()),
     }
 }
 
-pub fn get_required_u64(opt: Option<u64>, field_name: &str) -> Result<u64, String> {
+pub fn get_required_u64(opt: Option<u64>, _field_name: &str) -> Result<u64, String> {
     match opt {
         Some(val) => Ok(val),
         None => Err("Missing required fields".to_string()),
     }
 }
 
-pub fn get_required_u8(opt: Option<u8>, field_name: &str) -> Result<u8, String> {
+pub fn get_required_u8(opt: Option<u8>, _field_name: &str) -> Result<u8, String> {
     match opt {
         Some(val) => Ok(val),
         None => Err("Missing required fields".to_string()),
     }
 }
+
+// Cluster chosen by the `x-solana-cluster` header or a `cluster` query
+// parameter; `None` means the registry default.
+pub struct ClusterSelection(pub Option<String>);
+
+#[derive(Deserialize)]
+struct ClusterQuery {
+    cluster: Option<String>,
+}
+
+#[axum::async_trait]
+impl<S> FromRequestParts<S> for ClusterSelection
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let from_header = parts
+            .headers
+            .get(CLUSTER_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+
+        if from_header.is_some() {
+            return Ok(ClusterSelection(from_header));
+        }
+
+        let from_query = Query::<ClusterQuery>::try_from_uri(&parts.uri)
+            .ok()
+            .and_then(|Query(q)| q.cluster)
+            .filter(|v| !v.trim().is_empty());
+
+        Ok(ClusterSelection(from_query))
+    }
+}
+
+// Principal named by the `x-api-key` header. `Authorization: Bearer` is left
+// to SIWS session tokens. `None` when no key was sent; an unknown key is
+// rejected outright.
+pub struct Principal(pub Option<String>);
+
+#[axum::async_trait]
+impl FromRequestParts<AppState> for Principal {
+    type Rejection = ResponseJson<ApiResponse<()>>;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let token = parts
+            .headers
+            .get(API_KEY_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.trim().to_string())
+            .filter(|t| !t.is_empty());
+
+        match token {
+            None => Ok(Principal(None)),
+            Some(token) => match state.api_keys.principal(&token) {
+                Some(name) => Ok(Principal(Some(name))),
+                None => Err(ResponseJson(ApiResponse::error("Invalid API key".to_string()))),
+            },
+        }
+    }
+}
+
+// Instruction in the shape the builder endpoints return it. `/send/sol` lists
+// bare addresses and `/send/token` omits writability, so both are accepted.
+#[derive(Deserialize, Debug)]
+pub struct InstructionInput {
+    #[serde(alias = "programId")]
+    pub program_id: String,
+    pub accounts: Vec<InstructionAccountInput>,
+    #[serde(alias = "instructionData", alias = "data")]
+    pub instruction_data: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum InstructionAccountInput {
+    Meta(AccountInfo),
+    Signer {
+        pubkey: String,
+        #[serde(rename = "isSigner")]
+        is_signer: bool,
+    },
+    Address(String),
+}
@@ -31,4 +31,463 @@ impl<T> ApiResponse<T> {
 pub struct KeypairResponse {
     pub pubkey: String,
     pub secret: String,
-}
\ No newline at end of file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mnemonic: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub derivation_path: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct InstructionResponse {
+    pub program_id: String,
+    pub accounts: Vec<AccountInfo>,
+    pub instruction_data: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AccountInfo {
+    pub pubkey: String,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+#[derive(Serialize)]
+pub struct SolTransferResponse {
+    pub program_id: String,
+    pub accounts: Vec<String>,
+    pub instruction_data: String,
+}
+
+#[derive(Serialize)]
+pub struct TokenTransferAccount {
+    pub pubkey: String,
+    #[serde(rename = "isSigner")]
+    pub is_signer: bool,
+}
+
+#[derive(Serialize)]
+pub struct TokenTransferResponse {
+    pub program_id: String,
+    pub accounts: Vec<TokenTransferAccount>,
+    pub instruction_data: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct MessageSignResponse {
+    pub signature: String,
+    pub public_key: String,
+    pub message: String,
+    pub format: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offchain_format: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct MessageVerifyResponse {
+    pub valid: bool,
+    pub message: String,
+    pub pubkey: String,
+    pub format: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offchain_format: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct MessageVerifyBatchItem {
+    pub index: usize,
+    pub valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct MessageVerifyBatchResponse {
+    pub total: usize,
+    pub valid: usize,
+    pub invalid: usize,
+    pub failed: usize,
+    pub results: Vec<MessageVerifyBatchItem>,
+}
+
+#[derive(Serialize)]
+pub struct DecodedInstruction {
+    pub program_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub program: Option<String>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub instruction_type: Option<String>,
+    pub accounts: Vec<AccountInfo>,
+    pub instruction_data: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub info: Option<serde_json::Value>,
+}
+
+#[derive(Serialize)]
+pub struct MessageHeaderResponse {
+    pub num_required_signatures: u8,
+    pub num_readonly_signed_accounts: u8,
+    pub num_readonly_unsigned_accounts: u8,
+}
+
+#[derive(Serialize)]
+pub struct AddressTableLookupResponse {
+    pub account_key: String,
+    pub writable_indexes: Vec<u8>,
+    pub readonly_indexes: Vec<u8>,
+}
+
+#[derive(Serialize)]
+pub struct TransactionDecodeResponse {
+    pub version: String,
+    pub signatures: Vec<String>,
+    pub header: MessageHeaderResponse,
+    pub account_keys: Vec<String>,
+    pub recent_blockhash: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub address_table_lookups: Vec<AddressTableLookupResponse>,
+    pub instructions: Vec<DecodedInstruction>,
+}
+
+#[derive(Serialize)]
+pub struct AccountDetailsResponse {
+    pub pubkey: String,
+    pub lamports: u64,
+    pub owner: String,
+    pub executable: bool,
+    pub data_length: usize,
+    pub rent_epoch: u64,
+}
+
+#[derive(Serialize)]
+pub struct BalanceResponse {
+    pub pubkey: String,
+    pub lamports: u64,
+    pub sol: f64,
+}
+
+#[derive(Serialize)]
+pub struct TokenHoldingResponse {
+    pub address: String,
+    pub program: String,
+    pub mint: String,
+    pub amount: String,
+    pub decimals: u8,
+    pub ui_amount: String,
+}
+
+#[derive(Serialize)]
+pub struct TokenHoldingsResponse {
+    pub owner: String,
+    pub tokens: Vec<TokenHoldingResponse>,
+}
+
+#[derive(Serialize)]
+pub struct MintStateResponse {
+    pub address: String,
+    pub program_id: String,
+    pub supply: u64,
+    pub decimals: u8,
+    pub is_initialized: bool,
+    pub mint_authority: Option<String>,
+    pub freeze_authority: Option<String>,
+    pub extensions: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct TokenAccountStateResponse {
+    pub address: String,
+    pub program_id: String,
+    pub mint: String,
+    pub owner: String,
+    pub amount: u64,
+    pub delegate: Option<String>,
+    pub delegated_amount: u64,
+    pub state: String,
+    pub is_native: bool,
+    pub rent_exempt_reserve: Option<u64>,
+    pub close_authority: Option<String>,
+    pub extensions: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct BalanceChangeResponse {
+    pub pubkey: String,
+    pub pre: u64,
+    pub post: u64,
+    pub change: i64,
+}
+
+#[derive(Serialize)]
+pub struct TokenBalanceChangeResponse {
+    pub account: String,
+    pub mint: String,
+    pub owner: Option<String>,
+    pub decimals: u8,
+    pub pre_amount: String,
+    pub post_amount: String,
+}
+
+#[derive(Serialize)]
+pub struct TransactionDetailsResponse {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub status: String,
+    pub confirmation_status: Option<String>,
+    pub fee: u64,
+    pub error: Option<String>,
+    pub logs: Vec<String>,
+    pub balances: Vec<BalanceChangeResponse>,
+    pub token_balances: Vec<TokenBalanceChangeResponse>,
+    pub instructions: Vec<DecodedInstruction>,
+}
+
+#[derive(Serialize)]
+pub struct SignatureInfoResponse {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub status: String,
+    pub confirmation_status: Option<String>,
+    pub error: Option<String>,
+    pub memo: Option<String>,
+    pub instructions: Vec<DecodedInstruction>,
+}
+
+#[derive(Serialize)]
+pub struct SignatureHistoryResponse {
+    pub pubkey: String,
+    pub signatures: Vec<SignatureInfoResponse>,
+    pub next_before: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct AirdropResponse {
+    pub signature: String,
+    pub pubkey: String,
+    pub lamports: u64,
+    pub confirmation_status: String,
+}
+
+#[derive(Serialize)]
+pub struct ClusterResponse {
+    pub name: String,
+    pub kind: crate::cluster::ClusterKind,
+    pub commitment: String,
+    pub default: bool,
+}
+
+#[derive(Serialize)]
+pub struct BlockhashResponse {
+    pub cluster: String,
+    pub blockhash: String,
+    pub last_valid_block_height: u64,
+    pub age_ms: u128,
+}
+
+#[derive(Serialize)]
+pub struct TransactionBuildResponse {
+    pub transaction: String,
+    pub message: String,
+    pub recent_blockhash: String,
+    pub last_valid_block_height: u64,
+    pub signers: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct RentDepositResponse {
+    pub account: String,
+    pub kind: String,
+    pub space: u64,
+    pub lamports: u64,
+    pub funder: String,
+}
+
+#[derive(Serialize)]
+pub struct FeeEstimateResponse {
+    pub fee_payer: String,
+    pub fee: u64,
+    pub rent_deposits: Vec<RentDepositResponse>,
+    pub rent_total: u64,
+    pub transfer_total: u64,
+    pub total: u64,
+}
+
+#[derive(Serialize)]
+pub struct TransactionSendResponse {
+    pub signature: String,
+    pub cluster: String,
+    pub last_valid_block_height: u64,
+    pub status: crate::tracker::TrackedStatus,
+}
+
+#[derive(Serialize)]
+pub struct TransactionStatusResponse {
+    pub signature: String,
+    pub status: crate::tracker::TrackedStatus,
+    pub tracked: bool,
+    pub cluster: String,
+    pub slot: Option<u64>,
+    pub error: Option<String>,
+    pub last_valid_block_height: Option<u64>,
+    pub rebroadcasts: u32,
+    pub elapsed_ms: Option<u128>,
+}
+
+#[derive(Serialize)]
+pub struct WatchResponse {
+    pub id: String,
+    pub address: String,
+    pub callback_url: String,
+    pub cluster: String,
+    pub active: bool,
+    pub created_at: u64,
+    pub last_signature: Option<String>,
+    pub deliveries: u64,
+    pub failed_deliveries: u64,
+    pub last_error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct DerivedKeypairResponse {
+    pub index: u32,
+    pub derivation_path: String,
+    pub pubkey: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct DerivedAddressResponse {
+    pub account: u32,
+    pub change: u32,
+    pub derivation_path: String,
+    pub pubkey: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct KeypairConvertResponse {
+    pub pubkey: String,
+    pub base58: String,
+    pub json: Vec<u8>,
+    pub hex: String,
+    pub base64: String,
+    pub seed: String,
+}
+
+#[derive(Serialize)]
+pub struct VanityResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub status: crate::vanity::VanityStatus,
+    pub prefix: String,
+    pub suffix: String,
+    pub case_sensitive: bool,
+    pub attempts: u64,
+    pub estimated_attempts: u64,
+    pub elapsed_ms: u128,
+    pub timeout_ms: u128,
+    pub pubkey: Option<String>,
+    pub secret: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct KeyResponse {
+    pub id: String,
+    pub label: Option<String>,
+    pub pubkey: String,
+    pub protection: crate::keystore::Protection,
+    pub created_at: u64,
+    pub last_used_at: Option<u64>,
+    pub disabled: bool,
+    pub rotated_from: Option<String>,
+    pub rotated_to: Option<String>,
+    pub policy: Option<crate::policy::SigningPolicy>,
+    pub daily_spend: Option<crate::policy::DailySpend>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approval: Option<ApprovalResponse>,
+}
+
+#[derive(Serialize)]
+pub struct KeyRotateResponse {
+    pub retired: KeyResponse,
+    pub replacement: KeyResponse,
+}
+
+#[derive(Serialize)]
+pub struct TransactionSignResponse {
+    pub transaction: String,
+    pub signature: Option<String>,
+    pub signer: String,
+    pub complete: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approval: Option<ApprovalResponse>,
+}
+
+// An executed transaction approval returns the signed transaction; an
+// executed policy change returns the updated key.
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum ApprovalExecuteResponse {
+    Transaction(Box<TransactionSignResponse>),
+    Key(Box<KeyResponse>),
+}
+
+#[derive(Serialize)]
+pub struct ApprovalResponse {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub status: crate::approvals::ApprovalStatus,
+    pub key_id: String,
+    pub pubkey: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cluster: Option<String>,
+    pub quorum: u32,
+    pub approvals: Vec<String>,
+    pub requested_by: Option<String>,
+    pub rejected_by: Option<String>,
+    pub rejection: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lamports: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tokens: Option<std::collections::HashMap<String, u64>>,
+    pub expiry: crate::approvals::ApprovalExpiry,
+    pub created_at: u64,
+    pub finished_at: Option<u64>,
+    pub signature: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<Vec<DecodedInstruction>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub policy: Option<crate::policy::SigningPolicy>,
+}
+
+#[derive(Serialize)]
+pub struct SiwsChallengeResponse {
+    pub domain: String,
+    pub address: Option<String>,
+    pub statement: Option<String>,
+    pub uri: Option<String>,
+    pub version: Option<String>,
+    pub chain_id: Option<String>,
+    pub nonce: Option<String>,
+    pub issued_at: Option<String>,
+    pub expiration_time: Option<String>,
+    pub message: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct SiwsSessionResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    pub address: String,
+    pub domain: String,
+    pub issued_at: u64,
+    pub expires_at: u64,
+}
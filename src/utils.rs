@@ -1,8 +1,39 @@
-use solana_sdk::{instruction::Instruction, pubkey::Pubkey, signature::Keypair};
-use std::str::FromStr;
+use bip39::{Language, Mnemonic, Seed};
+use solana_sdk::{
+    derivation_path::DerivationPath,
+    instruction::{AccountMeta, Instruction},
+    message::Message,
+    offchain_message::{MessageFormat, OffchainMessage},
+    packet::PACKET_DATA_SIZE,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+    signer::keypair::{keypair_from_seed, keypair_from_seed_and_derivation_path},
+    transaction::Transaction,
+};
+use rand::RngCore;
+use std::{
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
 use base64::{engine::general_purpose, Engine as _};
 
-use crate::types::response::{InstructionResponse, AccountInfo};
+use crate::types::{
+    request::{InstructionAccountInput, InstructionInput},
+    response::{AccountInfo, InstructionResponse},
+};
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+pub fn random_hex(len: usize) -> String {
+    let mut bytes = vec![0u8; len];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
 
 pub fn validate_pubkey(pubkey_str: &str) -> Result<Pubkey, String> {
     let trimmed = pubkey_str.trim();
@@ -29,6 +60,195 @@ pub fn validate_pubkey(pubkey_str: &str) -> Result<Pubkey, String> {
     }
 }
 
+// Same checks as `validate_pubkey`, but the all-zero key is accepted since it
+// is the System Program id and a legitimate account reference.
+pub fn validate_account_pubkey(pubkey_str: &str) -> Result<Pubkey, String> {
+    if pubkey_str.trim() == Pubkey::default().to_string() {
+        return Ok(Pubkey::default());
+    }
+    validate_pubkey(pubkey_str)
+}
+
+pub fn decode_bytes(input: &str, encoding: &str) -> Result<Vec<u8>, String> {
+    let trimmed = input.trim();
+    match encoding {
+        "hex" => hex::decode(trimmed.trim_start_matches("0x"))
+            .map_err(|_| "Invalid data: Invalid hex encoding".to_string()),
+        "base58" => bs58::decode(trimmed)
+            .into_vec()
+            .map_err(|_| "Invalid data: Invalid base58 encoding".to_string()),
+        "base64" => general_purpose::STANDARD
+            .decode(trimmed)
+            .map_err(|_| "Invalid data: Invalid base64 encoding".to_string()),
+        _ => Err("Invalid encoding: must be one of hex, base58, base64".to_string()),
+    }
+}
+
+fn normalize_encoding(encoding: Option<&str>, default: &str) -> String {
+    match encoding.map(str::trim).filter(|e| !e.is_empty()) {
+        Some(e) => e.to_lowercase(),
+        None => default.to_string(),
+    }
+}
+
+pub fn encode_bytes(bytes: &[u8], encoding: &str) -> Result<String, String> {
+    match encoding {
+        "hex" => Ok(hex::encode(bytes)),
+        "base58" => Ok(bs58::encode(bytes).into_string()),
+        "base64" => Ok(general_purpose::STANDARD.encode(bytes)),
+        _ => Err("Invalid encoding: must be one of hex, base58, base64".to_string()),
+    }
+}
+
+// Message payloads are UTF-8 text by default; `messageEncoding` lets callers
+// sign arbitrary bytes such as hashes or serialized structs.
+pub fn decode_message(message: &str, encoding: Option<&str>) -> Result<Vec<u8>, String> {
+    let encoding = normalize_encoding(encoding, "utf8");
+    match encoding.as_str() {
+        "utf8" | "utf-8" => Ok(message.as_bytes().to_vec()),
+        "hex" | "base58" | "base64" => decode_bytes(message, &encoding)
+            .map_err(|_| format!("Invalid message: Invalid {} encoding", encoding)),
+        _ => Err("Invalid messageEncoding: must be one of utf8, hex, base58, base64".to_string()),
+    }
+}
+
+pub fn signature_encoding(encoding: Option<&str>) -> Result<String, String> {
+    let encoding = normalize_encoding(encoding, "base64");
+    match encoding.as_str() {
+        "hex" | "base58" | "base64" => Ok(encoding),
+        _ => Err("Invalid signatureEncoding: must be one of base64, base58, hex".to_string()),
+    }
+}
+
+pub fn parse_signature(signature_str: &str, encoding: Option<&str>) -> Result<Signature, String> {
+    let encoding = signature_encoding(encoding)?;
+
+    let signature_bytes = decode_bytes(signature_str, &encoding)
+        .map_err(|_| format!("Invalid signature: Invalid {} encoding", encoding))?;
+
+    let sig_array = <[u8; 64]>::try_from(signature_bytes)
+        .map_err(|_| "Invalid signature: Invalid signature length".to_string())?;
+
+    Ok(Signature::from(sig_array))
+}
+
+// Bytes that are actually signed for a message. `offchain` wraps the payload
+// in the Solana off-chain message envelope (signing domain, version, format
+// byte and length header) that wallets such as Phantom and Ledger produce;
+// the format byte is picked from the content. Returns the envelope format, if
+// any.
+pub fn signable_message(
+    message: &[u8],
+    format: Option<&str>,
+) -> Result<(Vec<u8>, Option<&'static str>), String> {
+    match format.map(|f| f.trim().to_lowercase()).as_deref() {
+        None | Some("") | Some("raw") => Ok((message.to_vec(), None)),
+        Some("offchain") => {
+            if std::str::from_utf8(message).is_err() {
+                return Err("Invalid message: off-chain format requires UTF-8 text".to_string());
+            }
+            let offchain = OffchainMessage::new(0, message)
+                .map_err(|_| "Invalid message: too long for off-chain format".to_string())?;
+            let bytes = offchain
+                .serialize()
+                .map_err(|_| "Invalid message: too long for off-chain format".to_string())?;
+            let name = match offchain.get_format() {
+                MessageFormat::RestrictedAscii => "restricted_ascii",
+                MessageFormat::LimitedUtf8 => "limited_utf8",
+                MessageFormat::ExtendedUtf8 => "extended_utf8",
+            };
+            Ok((bytes, Some(name)))
+        }
+        Some(_) => Err("Invalid format: must be one of raw, offchain".to_string()),
+    }
+}
+
+// Rejects instructions that could never be sent because the smallest
+// transaction carrying them already exceeds the packet size. Every sendable
+// transaction has a fee payer, so when no account signs a placeholder payer
+// is counted (its key and signature).
+pub fn validate_instruction_size(instruction: &Instruction) -> Result<(), String> {
+    let payer = instruction
+        .accounts
+        .iter()
+        .find(|acc| acc.is_signer)
+        .map(|acc| acc.pubkey)
+        .unwrap_or_else(Pubkey::new_unique);
+    let message = Message::new(std::slice::from_ref(instruction), Some(&payer));
+    let transaction = Transaction::new_unsigned(message);
+
+    let size = bincode::serialized_size(&transaction)
+        .map_err(|_| "Failed to serialize transaction".to_string())?;
+
+    if size as usize > PACKET_DATA_SIZE {
+        return Err("Instruction too large: transaction exceeds 1232 bytes".to_string());
+    }
+
+    Ok(())
+}
+
+pub fn account_metas_from_info(
+    accounts: &[AccountInfo],
+    program_id: &Pubkey,
+) -> Result<Vec<AccountMeta>, String> {
+    accounts
+        .iter()
+        .map(|account| {
+            let pubkey = validate_account_pubkey(&account.pubkey)?;
+
+            if pubkey == *program_id && account.is_writable {
+                return Err("Invalid accounts: program id cannot be writable".to_string());
+            }
+
+            Ok(if account.is_writable {
+                AccountMeta::new(pubkey, account.is_signer)
+            } else {
+                AccountMeta::new_readonly(pubkey, account.is_signer)
+            })
+        })
+        .collect()
+}
+
+// Accounts given without flags are treated as writable, and a bare address
+// only signs when it is the fee payer.
+pub fn convert_input_to_instruction(
+    instruction: &InstructionInput,
+    fee_payer: &Pubkey,
+) -> Result<Instruction, String> {
+    let program_id = validate_account_pubkey(&instruction.program_id)?;
+
+    let accounts: Vec<AccountInfo> = instruction
+        .accounts
+        .iter()
+        .map(|account| match account {
+            InstructionAccountInput::Meta(meta) => AccountInfo {
+                pubkey: meta.pubkey.clone(),
+                is_signer: meta.is_signer,
+                is_writable: meta.is_writable,
+            },
+            InstructionAccountInput::Signer { pubkey, is_signer } => AccountInfo {
+                pubkey: pubkey.clone(),
+                is_signer: *is_signer,
+                is_writable: true,
+            },
+            InstructionAccountInput::Address(pubkey) => AccountInfo {
+                pubkey: pubkey.clone(),
+                is_signer: pubkey.trim() == fee_payer.to_string(),
+                is_writable: true,
+            },
+        })
+        .collect();
+
+    let accounts = account_metas_from_info(&accounts, &program_id)?;
+    let data = decode_bytes(&instruction.instruction_data, "base64")?;
+
+    Ok(Instruction {
+        program_id,
+        accounts,
+        data,
+    })
+}
+
 pub fn convert_instruction_to_response(instruction: Instruction) -> InstructionResponse {
     let accounts = instruction
         .accounts
@@ -47,26 +267,119 @@ pub fn convert_instruction_to_response(instruction: Instruction) -> InstructionR
     }
 }
 
-pub fn parse_secret_key(secret_str: &str) -> Result<Keypair, String> {
+// Accepts a 64-byte keypair or a 32-byte ed25519 seed in base58, hex, base64
+// or the Solana CLI JSON byte array. Without a hint the encoding is guessed
+// from the characters used; the hint settles strings valid in several.
+pub fn parse_secret_key(
+    secret_str: &str,
+    format: Option<&str>,
+) -> Result<Keypair, String> {
     let trimmed = secret_str.trim();
-    
+
     if trimmed.is_empty() {
         return Err("Invalid private key".to_string());
     }
 
-    if trimmed.len() < 80 || trimmed.len() > 90 {
-        return Err("Invalid private key".to_string());
+    let format = match format.map(str::trim).filter(|f| !f.is_empty()) {
+        Some(f) => f.to_lowercase(),
+        None => detect_secret_format(trimmed).to_string(),
+    };
+
+    let decoded = match format.as_str() {
+        "json" => serde_json::from_str::<Vec<u8>>(trimmed).ok(),
+        "hex" => hex::decode(trimmed.trim_start_matches("0x")).ok(),
+        "base58" => bs58::decode(trimmed).into_vec().ok(),
+        "base64" => general_purpose::STANDARD.decode(trimmed).ok(),
+        _ => {
+            return Err("Invalid format: must be one of base58, json, hex, base64".to_string());
+        }
     }
+    .ok_or_else(|| "Invalid private key".to_string())?;
+
+    keypair_from_secret_bytes(&decoded)
+}
 
-    let decoded = bs58::decode(trimmed)
+fn detect_secret_format(secret: &str) -> &'static str {
+    if secret.starts_with('[') {
+        "json"
+    } else if matches!(secret.trim_start_matches("0x").len(), 64 | 128)
+        && secret.trim_start_matches("0x").chars().all(|c| c.is_ascii_hexdigit())
+    {
+        "hex"
+    } else if secret.contains(['=', '+', '/']) {
+        "base64"
+    } else if bs58::decode(secret)
         .into_vec()
-        .map_err(|_| "Invalid private key".to_string())?;
+        .is_ok_and(|bytes| matches!(bytes.len(), 32 | 64))
+    {
+        "base58"
+    } else {
+        "base64"
+    }
+}
 
-    if decoded.len() != 64 {
-        return Err("Invalid private key".to_string());
+fn keypair_from_secret_bytes(bytes: &[u8]) -> Result<Keypair, String> {
+    match bytes.len() {
+        32 => keypair_from_seed(bytes).map_err(|_| "Invalid private key".to_string()),
+        64 => {
+            let keypair = Keypair::from_bytes(bytes).map_err(|_| "Invalid private key".to_string())?;
+            let expected = keypair_from_seed(&bytes[..32]).map_err(|_| "Invalid private key".to_string())?;
+            if keypair.pubkey() != expected.pubkey() {
+                return Err("Invalid private key: public key does not match secret".to_string());
+            }
+            Ok(keypair)
+        }
+        _ => Err("Invalid private key".to_string()),
+    }
+}
+
+pub fn parse_mnemonic(phrase: &str) -> Result<Mnemonic, String> {
+    let normalized = phrase
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+
+    Mnemonic::from_phrase(&normalized, Language::English).map_err(|_| "Invalid mnemonic".to_string())
+}
+
+pub fn mnemonic_seed(mnemonic: &Mnemonic, passphrase: &str) -> Vec<u8> {
+    Seed::new(mnemonic, passphrase).as_bytes().to_vec()
+}
+
+pub fn derive_keypair(seed: &[u8], path: &DerivationPath) -> Result<Keypair, String> {
+    keypair_from_seed_and_derivation_path(seed, Some(path.clone()))
+        .map_err(|_| "Failed to derive keypair".to_string())
+}
+
+// Fills `{account}` and `{change}` in a SLIP-0010 path template. ed25519 only
+// supports hardened derivation, so every component must end in `'`.
+pub fn render_derivation_path(
+    template: &str,
+    account: u32,
+    change: u32,
+) -> Result<DerivationPath, String> {
+    let rendered = template
+        .trim()
+        .replace("{account}", &account.to_string())
+        .replace("{change}", &change.to_string());
+
+    let mut components = rendered.split('/');
+    if components.next() != Some("m") {
+        return Err("Invalid path: must start with m/".to_string());
     }
 
-    Keypair::from_bytes(&decoded).map_err(|_| "Invalid private key".to_string())
+    for component in components {
+        let index = component
+            .strip_suffix('\'')
+            .ok_or_else(|| "Invalid path: ed25519 derivation requires hardened indexes".to_string())?;
+        match index.parse::<u32>() {
+            Ok(i) if i < 1 << 31 => {}
+            _ => return Err(format!("Invalid path component: {}", component)),
+        }
+    }
+
+    DerivationPath::from_absolute_path_str(&rendered).map_err(|_| "Invalid path".to_string())
 }
 
 pub fn validate_amount(amount: u64, max_allowed: Option<u64>) -> Result<u64, String> {
@@ -74,10 +387,10 @@ pub fn validate_amount(amount: u64, max_allowed: Option<u64>) -> Result<u64, Str
         return Err("Invalid amount: must be greater than 0".to_string());
     }
 
-    if let Some(max) = max_allowed {
-        if amount > max {
-            return Err("Invalid amount: amount too large".to_string());
-        }
+    if let Some(max) = max_allowed
+        && amount > max
+    {
+        return Err("Invalid amount: amount too large".to_string());
     }
 
     Ok(amount)
@@ -0,0 +1,309 @@
+use serde::Serialize;
+use solana_sdk::signature::{Keypair, Signer};
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
+use tokio::sync::RwLock;
+
+use crate::utils::random_hex;
+
+const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const MAX_PATTERN_LEN: usize = 8;
+const MAX_RUNNING_SEARCHES: usize = 4;
+const CHECK_EVERY: u64 = 256;
+const RETENTION: Duration = Duration::from_secs(3600);
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum VanityStatus {
+    Running,
+    Found,
+    Expired,
+    Cancelled,
+}
+
+#[derive(Clone, Debug)]
+pub struct VanityPattern {
+    pub prefix: String,
+    pub suffix: String,
+    pub case_sensitive: bool,
+}
+
+impl VanityPattern {
+    pub fn new(prefix: &str, suffix: &str, case_sensitive: bool) -> Result<Self, String> {
+        let prefix = prefix.trim();
+        let suffix = suffix.trim();
+
+        if prefix.is_empty() && suffix.is_empty() {
+            return Err("Missing required fields".to_string());
+        }
+
+        if prefix.len() + suffix.len() > MAX_PATTERN_LEN {
+            return Err(format!(
+                "Invalid pattern: prefix and suffix together must be at most {} characters",
+                MAX_PATTERN_LEN
+            ));
+        }
+
+        for c in prefix.chars().chain(suffix.chars()) {
+            let valid = if case_sensitive {
+                BASE58_ALPHABET.contains(c)
+            } else {
+                BASE58_ALPHABET.contains(c.to_ascii_lowercase())
+                    || BASE58_ALPHABET.contains(c.to_ascii_uppercase())
+            };
+            if !valid {
+                return Err(format!("Invalid pattern: '{}' is not a base58 character", c));
+            }
+        }
+
+        let normalize = |s: &str| {
+            if case_sensitive {
+                s.to_string()
+            } else {
+                s.to_ascii_lowercase()
+            }
+        };
+
+        Ok(Self {
+            prefix: normalize(prefix),
+            suffix: normalize(suffix),
+            case_sensitive,
+        })
+    }
+
+    fn matches(&self, address: &str) -> bool {
+        if self.case_sensitive {
+            return address.starts_with(&self.prefix) && address.ends_with(&self.suffix);
+        }
+
+        address.len() >= self.prefix.len().max(self.suffix.len())
+            && address.as_bytes()[..self.prefix.len()].eq_ignore_ascii_case(self.prefix.as_bytes())
+            && address.as_bytes()[address.len() - self.suffix.len()..]
+                .eq_ignore_ascii_case(self.suffix.as_bytes())
+    }
+
+    // Rough number of keys to try, treating each character as uniformly
+    // distributed. Case-insensitive letters that exist in both cases count
+    // twice as likely.
+    pub fn estimated_attempts(&self) -> u64 {
+        self.prefix
+            .chars()
+            .chain(self.suffix.chars())
+            .fold(1u64, |acc, c| {
+                let choices = if !self.case_sensitive
+                    && BASE58_ALPHABET.contains(c.to_ascii_lowercase())
+                    && BASE58_ALPHABET.contains(c.to_ascii_uppercase())
+                    && c.is_ascii_alphabetic()
+                {
+                    29
+                } else {
+                    58
+                };
+                acc.saturating_mul(choices)
+            })
+    }
+}
+
+#[derive(Default)]
+pub struct SearchProgress {
+    pub attempts: AtomicU64,
+    pub cancelled: AtomicBool,
+}
+
+#[derive(Clone)]
+pub struct VanityJob {
+    pub id: String,
+    pub pattern: VanityPattern,
+    pub status: VanityStatus,
+    pub progress: Arc<SearchProgress>,
+    pub timeout: Duration,
+    pub started_at: Instant,
+    pub finished_at: Option<Instant>,
+    pub pubkey: Option<String>,
+    pub secret: Option<String>,
+}
+
+impl VanityJob {
+    pub fn elapsed(&self) -> Duration {
+        self.finished_at
+            .unwrap_or_else(Instant::now)
+            .duration_since(self.started_at)
+    }
+}
+
+#[derive(Default)]
+pub struct VanityJobs {
+    jobs: RwLock<HashMap<String, VanityJob>>,
+    running: Arc<AtomicUsize>,
+}
+
+struct RunningGuard(Arc<AtomicUsize>);
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+// Dropped together with a synchronous request, so workers stop when the
+// client disconnects instead of running out the whole time budget.
+struct CancelOnDrop(Arc<SearchProgress>);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+impl VanityJobs {
+    fn reserve(&self) -> Result<RunningGuard, String> {
+        let reserved = self
+            .running
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |running| {
+                (running < MAX_RUNNING_SEARCHES).then_some(running + 1)
+            });
+
+        match reserved {
+            Ok(_) => Ok(RunningGuard(self.running.clone())),
+            Err(_) => Err("Too many vanity searches running, try again later".to_string()),
+        }
+    }
+
+    pub async fn search(
+        &self,
+        pattern: VanityPattern,
+        timeout: Duration,
+        progress: Arc<SearchProgress>,
+    ) -> Result<Option<Keypair>, String> {
+        let _guard = self.reserve()?;
+        let _cancel = CancelOnDrop(progress.clone());
+        Ok(search(Arc::new(pattern), timeout, progress).await)
+    }
+
+    pub async fn start(
+        self: Arc<Self>,
+        pattern: VanityPattern,
+        timeout: Duration,
+    ) -> Result<VanityJob, String> {
+        let guard = self.reserve()?;
+
+        let job = VanityJob {
+            id: random_hex(16),
+            pattern: pattern.clone(),
+            status: VanityStatus::Running,
+            progress: Arc::new(SearchProgress::default()),
+            timeout,
+            started_at: Instant::now(),
+            finished_at: None,
+            pubkey: None,
+            secret: None,
+        };
+
+        self.jobs.write().await.insert(job.id.clone(), job.clone());
+
+        let id = job.id.clone();
+        let progress = job.progress.clone();
+
+        tokio::spawn(async move {
+            let found = search(Arc::new(pattern), timeout, progress.clone()).await;
+            drop(guard);
+
+            let status = match &found {
+                Some(_) => VanityStatus::Found,
+                None if progress.cancelled.load(Ordering::Relaxed) => VanityStatus::Cancelled,
+                None => VanityStatus::Expired,
+            };
+
+            if let Some(job) = self.jobs.write().await.get_mut(&id) {
+                job.status = status;
+                job.finished_at = Some(Instant::now());
+                if let Some(keypair) = found {
+                    job.pubkey = Some(keypair.pubkey().to_string());
+                    job.secret = Some(bs58::encode(keypair.to_bytes()).into_string());
+                }
+            }
+
+            tokio::time::sleep(RETENTION).await;
+            self.jobs.write().await.remove(&id);
+        });
+
+        Ok(job)
+    }
+
+    pub async fn get(&self, id: &str) -> Option<VanityJob> {
+        self.jobs.read().await.get(id).cloned()
+    }
+
+    // Stops the search if it is still running and forgets the job, so a
+    // found secret is not kept around after the caller is done with it.
+    pub async fn cancel(&self, id: &str) -> Option<VanityJob> {
+        let mut job = self.jobs.write().await.remove(id)?;
+        if job.status == VanityStatus::Running {
+            job.progress.cancelled.store(true, Ordering::Relaxed);
+            job.status = VanityStatus::Cancelled;
+            job.finished_at = Some(Instant::now());
+        }
+        Some(job)
+    }
+}
+
+// Runs one worker per core on the blocking pool. Workers stop as soon as one
+// of them finds a match, the time budget runs out or the search is cancelled.
+async fn search(
+    pattern: Arc<VanityPattern>,
+    timeout: Duration,
+    progress: Arc<SearchProgress>,
+) -> Option<Keypair> {
+    let workers = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1);
+    let deadline = Instant::now() + timeout;
+    let done = Arc::new(AtomicBool::new(false));
+
+    let handles: Vec<_> = (0..workers)
+        .map(|_| {
+            let pattern = pattern.clone();
+            let progress = progress.clone();
+            let done = done.clone();
+
+            tokio::task::spawn_blocking(move || {
+                let mut local: u64 = 0;
+                loop {
+                    if local.is_multiple_of(CHECK_EVERY) {
+                        if local > 0 {
+                            progress.attempts.fetch_add(CHECK_EVERY, Ordering::Relaxed);
+                        }
+                        if done.load(Ordering::Relaxed)
+                            || progress.cancelled.load(Ordering::Relaxed)
+                            || Instant::now() >= deadline
+                        {
+                            return None;
+                        }
+                    }
+                    local += 1;
+
+                    let keypair = Keypair::new();
+                    if pattern.matches(&keypair.pubkey().to_string()) {
+                        progress.attempts.fetch_add(local % CHECK_EVERY, Ordering::Relaxed);
+                        done.store(true, Ordering::Relaxed);
+                        return Some(keypair);
+                    }
+                }
+            })
+        })
+        .collect();
+
+    let mut found = None;
+    for handle in handles {
+        if let Ok(Some(keypair)) = handle.await {
+            found.get_or_insert(keypair);
+        }
+    }
+    found
+}
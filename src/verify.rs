@@ -0,0 +1,160 @@
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use ed25519_dalek::PublicKey;
+use rayon::prelude::*;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+const BATCH_SIZE: usize = 64;
+
+pub struct SignedMessage {
+    pub pubkey: Pubkey,
+    pub signature: Signature,
+    pub message: Vec<u8>,
+}
+
+impl SignedMessage {
+    pub fn verify(&self) -> bool {
+        self.signature.verify(&self.pubkey.to_bytes(), &self.message)
+    }
+
+    // Batch verification can accept keys and R points with a small-order
+    // component where the strict `Signature::verify` does not. Such items are
+    // left to the strict path so both give the same answer.
+    fn batchable(&self) -> bool {
+        let r: [u8; 32] = self.signature.as_ref()[..32].try_into().unwrap_or_default();
+        [self.pubkey.to_bytes(), r].iter().all(|point| {
+            CompressedEdwardsY(*point)
+                .decompress()
+                .is_some_and(|p| !p.is_small_order() && p.is_torsion_free())
+        })
+    }
+}
+
+fn verify_batch(chunk: &[SignedMessage]) -> Option<()> {
+    if !chunk.iter().all(SignedMessage::batchable) {
+        return None;
+    }
+
+    let messages: Vec<&[u8]> = chunk.iter().map(|item| item.message.as_slice()).collect();
+    let signatures = chunk
+        .iter()
+        .map(|item| ed25519_dalek::Signature::from_bytes(item.signature.as_ref()).ok())
+        .collect::<Option<Vec<_>>>()?;
+    let public_keys = chunk
+        .iter()
+        .map(|item| PublicKey::from_bytes(item.pubkey.as_ref()).ok())
+        .collect::<Option<Vec<_>>>()?;
+
+    ed25519_dalek::verify_batch(&messages, &signatures, &public_keys).ok()
+}
+
+// Verifies chunks in parallel, each as one ed25519 batch. A chunk that fails
+// as a whole is re-checked item by item to find the bad signatures.
+pub fn verify_all(items: &[SignedMessage]) -> Vec<bool> {
+    items
+        .par_chunks(BATCH_SIZE)
+        .flat_map_iter(|chunk| match verify_batch(chunk) {
+            Some(()) => vec![true; chunk.len()],
+            None => chunk.iter().map(SignedMessage::verify).collect(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use curve25519_dalek::{
+        constants::{ED25519_BASEPOINT_POINT, EIGHT_TORSION},
+        edwards::EdwardsPoint,
+        scalar::Scalar,
+    };
+    use rand::RngCore;
+    use sha2::{Digest, Sha512};
+
+    fn random_scalar() -> Scalar {
+        let mut bytes = [0u8; 64];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        Scalar::from_bytes_mod_order_wide(&bytes)
+    }
+
+    // Signs by hand so R and A can carry a small-order component. The
+    // torsion point has order 2, so a batch misses it for every even z.
+    fn torsioned(torsion_r: bool, torsion_a: bool) -> SignedMessage {
+        let torsion = EIGHT_TORSION[4];
+        let secret = random_scalar();
+        let nonce = random_scalar();
+        let message = b"hello".to_vec();
+
+        let mut public = secret * ED25519_BASEPOINT_POINT;
+        let mut r = nonce * ED25519_BASEPOINT_POINT;
+        if torsion_a {
+            public += torsion;
+        }
+        if torsion_r {
+            r += torsion;
+        }
+        let (public, r) = (public.compress(), r.compress());
+
+        let mut hash = Sha512::new();
+        hash.update(r.as_bytes());
+        hash.update(public.as_bytes());
+        hash.update(&message);
+        let k = Scalar::from_bytes_mod_order_wide(&hash.finalize().into());
+        let s = nonce + k * secret;
+
+        let mut signature = [0u8; 64];
+        signature[..32].copy_from_slice(r.as_bytes());
+        signature[32..].copy_from_slice(s.as_bytes());
+
+        SignedMessage {
+            pubkey: Pubkey::new_from_array(public.to_bytes()),
+            signature: Signature::from(signature),
+            message,
+        }
+    }
+
+    #[test]
+    fn hand_signed_messages_verify() {
+        let item = torsioned(false, false);
+        assert!(item.verify());
+        assert!(item.batchable());
+        assert_eq!(verify_all(&[torsioned(false, false), item]), vec![true, true]);
+    }
+
+    #[test]
+    fn torsioned_points_get_the_same_answer_in_a_batch() {
+        for (torsion_r, torsion_a) in [(true, false), (false, true)] {
+            let item = torsioned(torsion_r, torsion_a);
+            let point = if torsion_r {
+                &item.signature.as_ref()[..32]
+            } else {
+                item.pubkey.as_ref()
+            };
+            let point = CompressedEdwardsY::from_slice(point).decompress().unwrap();
+            assert!(!point.is_small_order());
+            assert!(!item.batchable());
+
+            // A torsioned R never matches the strict equation; a torsioned key
+            // does whenever the challenge scalar cancels the torsion.
+            let expected = item.verify();
+            if torsion_r {
+                assert!(!expected);
+            }
+
+            // The batch coefficients are random, so retry enough times that
+            // an accepted torsion component would show up.
+            let batch = [torsioned(false, false), item];
+            for _ in 0..32 {
+                assert_eq!(verify_all(&batch), vec![true, expected]);
+            }
+        }
+    }
+
+    #[test]
+    fn small_order_points_are_not_batchable() {
+        let identity = EdwardsPoint::default().compress();
+        let mut item = torsioned(false, false);
+        item.pubkey = Pubkey::new_from_array(identity.to_bytes());
+        assert!(!item.verify());
+        assert!(!item.batchable());
+    }
+}
@@ -0,0 +1,502 @@
+use hmac::{Hmac, Mac};
+use serde_json::{Value, json};
+use sha2::Sha256;
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use solana_transaction_status::EncodedConfirmedTransactionWithStatusMeta;
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
+use tokio::sync::RwLock;
+
+use crate::{
+    cluster::{Cluster, ClusterRegistry},
+    decoder::{confirmed_transaction_account_keys, token_balance_changes},
+    rpc::{fetch_transaction, rpc_error},
+    utils::unix_now,
+};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+const SIGNATURE_PAGE_LIMIT: usize = 100;
+const DELIVERY_ATTEMPTS: u32 = 5;
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+// Every watch polls the RPC node on its own, so the registry is capped.
+const MAX_WATCHES: usize = 100;
+
+pub const SIGNATURE_HEADER: &str = "x-webhook-signature";
+pub const WATCH_ID_HEADER: &str = "x-webhook-id";
+
+#[derive(Clone)]
+pub struct Watch {
+    pub id: String,
+    pub address: Pubkey,
+    pub callback_url: String,
+    pub secret: String,
+    pub cluster: String,
+    pub active: bool,
+    pub created_at: u64,
+    pub last_signature: Option<String>,
+    pub deliveries: u64,
+    pub failed_deliveries: u64,
+    pub last_error: Option<String>,
+}
+
+pub struct WatchRegistry {
+    watches: RwLock<HashMap<String, Watch>>,
+    http: reqwest::Client,
+}
+
+pub fn sign_payload(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+impl Default for WatchRegistry {
+    fn default() -> Self {
+        Self {
+            watches: RwLock::new(HashMap::new()),
+            http: reqwest::Client::builder()
+                .timeout(DELIVERY_TIMEOUT)
+                // A redirect could lead past the callback host checks.
+                .redirect(reqwest::redirect::Policy::none())
+                .build()
+                .expect("Failed to build HTTP client"),
+        }
+    }
+}
+
+impl WatchRegistry {
+    pub async fn insert(&self, watch: Watch) -> Result<(), String> {
+        let mut watches = self.watches.write().await;
+        if watches.len() >= MAX_WATCHES {
+            return Err("Too many watches, delete one first".to_string());
+        }
+        watches.insert(watch.id.clone(), watch);
+        Ok(())
+    }
+
+    pub async fn get(&self, id: &str) -> Option<Watch> {
+        self.watches.read().await.get(id).cloned()
+    }
+
+    pub async fn list(&self) -> Vec<Watch> {
+        let mut watches: Vec<Watch> = self.watches.read().await.values().cloned().collect();
+        watches.sort_by_key(|w| w.created_at);
+        watches
+    }
+
+    pub async fn update(&self, id: &str, apply: impl FnOnce(&mut Watch)) -> Option<Watch> {
+        let mut watches = self.watches.write().await;
+        let watch = watches.get_mut(id)?;
+        apply(watch);
+        Some(watch.clone())
+    }
+
+    pub async fn remove(&self, id: &str) -> Option<Watch> {
+        self.watches.write().await.remove(id)
+    }
+
+    pub fn spawn_monitor(self: Arc<Self>, clusters: Arc<ClusterRegistry>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(POLL_INTERVAL);
+            loop {
+                interval.tick().await;
+
+                for watch in self.list().await.into_iter().filter(|w| w.active) {
+                    let Ok(cluster) = clusters.resolve(Some(&watch.cluster)) else {
+                        continue;
+                    };
+                    if let Err(e) = self.poll(cluster, &watch).await {
+                        println!("⚠️  Watch {} poll failed: {}", watch.id, e);
+                    }
+                }
+            }
+        });
+    }
+
+    // Until a signature has been seen, only transactions with a block time at
+    // or after registration are reported, so history is never replayed.
+    async fn poll(self: &Arc<Self>, cluster: &Cluster, watch: &Watch) -> Result<(), String> {
+        let until = match &watch.last_signature {
+            Some(sig) => Some(Signature::from_str(sig).map_err(|e| e.to_string())?),
+            None => None,
+        };
+
+        // Pages come back newest first; keep going with `before` until the
+        // node has nothing newer than `until` left to return.
+        let mut signatures = Vec::new();
+        let mut before = None;
+        loop {
+            let config = GetConfirmedSignaturesForAddress2Config {
+                before,
+                until,
+                limit: Some(SIGNATURE_PAGE_LIMIT),
+                commitment: Some(cluster.commitment),
+            };
+
+            let page = cluster
+                .client
+                .get_signatures_for_address_with_config(&watch.address, config)
+                .await
+                .map_err(rpc_error)?;
+
+            let Some(oldest) = page.last() else {
+                break;
+            };
+
+            let exhausted = page.len() < SIGNATURE_PAGE_LIMIT
+                || (until.is_none()
+                    && oldest
+                        .block_time
+                        .is_none_or(|t| t < watch.created_at as i64));
+            before = Some(Signature::from_str(&oldest.signature).map_err(|e| e.to_string())?);
+            signatures.extend(page);
+
+            if exhausted {
+                break;
+            }
+        }
+
+        // Oldest first, moving the cursor past each signature as soon as it is
+        // handled, so a failure part-way through never redelivers earlier ones.
+        for info in signatures.iter().rev() {
+            let fresh = info.err.is_none()
+                && (until.is_some()
+                    || info.block_time.is_some_and(|t| t >= watch.created_at as i64));
+
+            if fresh {
+                let signature = Signature::from_str(&info.signature).map_err(|e| e.to_string())?;
+                let tx = fetch_transaction(&cluster.client, &signature).await?;
+                let transfers = incoming_transfers(&tx, &watch.address.to_string());
+
+                if !transfers.is_empty() {
+                    let payload = json!({
+                        "event": "transfer.incoming",
+                        "watchId": watch.id,
+                        "address": watch.address.to_string(),
+                        "cluster": cluster.name,
+                        "signature": info.signature,
+                        "slot": tx.slot,
+                        "blockTime": tx.block_time,
+                        "transfers": transfers,
+                        "timestamp": unix_now(),
+                    });
+
+                    tokio::spawn(self.clone().deliver(watch.clone(), payload));
+                }
+            }
+
+            self.update(&watch.id, |w| w.last_signature = Some(info.signature.clone()))
+                .await;
+        }
+
+        Ok(())
+    }
+
+    async fn deliver(self: Arc<Self>, watch: Watch, payload: Value) {
+        let body = payload.to_string().into_bytes();
+        let signature = sign_payload(&watch.secret, &body);
+        let mut backoff = INITIAL_BACKOFF;
+        let mut last_error = String::new();
+
+        for attempt in 1..=DELIVERY_ATTEMPTS {
+            let result = self
+                .http
+                .post(&watch.callback_url)
+                .header("content-type", "application/json")
+                .header(SIGNATURE_HEADER, &signature)
+                .header(WATCH_ID_HEADER, &watch.id)
+                .body(body.clone())
+                .send()
+                .await;
+
+            match result {
+                Ok(response) if response.status().is_success() => {
+                    self.update(&watch.id, |w| {
+                        w.deliveries += 1;
+                        w.last_error = None;
+                    })
+                    .await;
+                    return;
+                }
+                Ok(response) => last_error = format!("Callback returned {}", response.status()),
+                Err(e) => last_error = format!("Callback failed: {}", e),
+            }
+
+            if attempt < DELIVERY_ATTEMPTS {
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+            }
+        }
+
+        println!("⚠️  Webhook delivery for {} gave up: {}", watch.id, last_error);
+        self.update(&watch.id, |w| {
+            w.failed_deliveries += 1;
+            w.last_error = Some(last_error);
+        })
+        .await;
+    }
+}
+
+fn incoming_transfers(tx: &EncodedConfirmedTransactionWithStatusMeta, address: &str) -> Vec<Value> {
+    let Some(meta) = tx.transaction.meta.clone() else {
+        return Vec::new();
+    };
+
+    let account_keys = confirmed_transaction_account_keys(tx);
+    let mut transfers = Vec::new();
+
+    if let Some(index) = account_keys.iter().position(|key| key == address) {
+        let pre = meta.pre_balances.get(index).copied().unwrap_or_default();
+        let post = meta.post_balances.get(index).copied().unwrap_or_default();
+        if post > pre {
+            transfers.push(json!({ "type": "sol", "lamports": post - pre }));
+        }
+    }
+
+    for change in token_balance_changes(
+        &account_keys,
+        Option::from(meta.pre_token_balances).unwrap_or_default(),
+        Option::from(meta.post_token_balances).unwrap_or_default(),
+    ) {
+        if change.owner.as_deref() != Some(address) && change.account != address {
+            continue;
+        }
+
+        let pre: u128 = change.pre_amount.parse().unwrap_or_default();
+        let post: u128 = change.post_amount.parse().unwrap_or_default();
+        if post > pre {
+            transfers.push(json!({
+                "type": "token",
+                "mint": change.mint,
+                "account": change.account,
+                "amount": (post - pre).to_string(),
+                "decimals": change.decimals,
+            }));
+        }
+    }
+
+    transfers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Json, Router, extract::State, routing::post};
+    use base64::{Engine as _, engine::general_purpose};
+    use solana_sdk::{hash::Hash, signer::keypair::Keypair, system_transaction};
+    use std::{collections::HashSet, sync::Mutex};
+
+    // Stands in for both the RPC node and the webhook receiver.
+    #[derive(Default)]
+    struct Stub {
+        // Newest first, as `getSignaturesForAddress` returns them.
+        signatures: Vec<String>,
+        transaction: String,
+        fail_once: Mutex<HashSet<String>>,
+        delivered: Mutex<Vec<String>>,
+    }
+
+    async fn rpc(State(stub): State<Arc<Stub>>, Json(request): Json<Value>) -> Json<Value> {
+        let params = &request["params"];
+        let result = match request["method"].as_str() {
+            Some("getSignaturesForAddress") => {
+                let config = &params[1];
+                let position = |key: &str| {
+                    config[key]
+                        .as_str()
+                        .and_then(|sig| stub.signatures.iter().position(|s| s == sig))
+                };
+                let start = position("before").map_or(0, |i| i + 1);
+                let end = position("until").unwrap_or(stub.signatures.len());
+                let limit = config["limit"].as_u64().unwrap_or(1000) as usize;
+
+                let page: Vec<Value> = stub.signatures[start..end.max(start)]
+                    .iter()
+                    .take(limit)
+                    .map(|sig| {
+                        json!({
+                            "signature": sig,
+                            "slot": 1,
+                            "err": null,
+                            "memo": null,
+                            "blockTime": 1_700_000_000,
+                            "confirmationStatus": "finalized",
+                        })
+                    })
+                    .collect();
+                json!(page)
+            }
+            Some("getTransaction") => {
+                let signature = params[0].as_str().unwrap_or_default().to_string();
+                if stub.fail_once.lock().unwrap().remove(&signature) {
+                    return Json(json!({
+                        "jsonrpc": "2.0",
+                        "id": request["id"],
+                        "error": { "code": -32000, "message": "node unavailable" },
+                    }));
+                }
+                json!({
+                    "slot": 1,
+                    "blockTime": 1_700_000_000,
+                    "transaction": [stub.transaction, "base64"],
+                    "meta": {
+                        "err": null,
+                        "status": { "Ok": null },
+                        "fee": 5000,
+                        "preBalances": [10_000_000, 0, 1],
+                        "postBalances": [8_995_000, 1_000_000, 1],
+                        "innerInstructions": [],
+                        "logMessages": [],
+                        "preTokenBalances": [],
+                        "postTokenBalances": [],
+                        "rewards": [],
+                    },
+                })
+            }
+            Some("getVersion") => json!({ "solana-core": "1.18.26", "feature-set": 1 }),
+            _ => Value::Null,
+        };
+
+        Json(json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }))
+    }
+
+    async fn webhook(State(stub): State<Arc<Stub>>, Json(payload): Json<Value>) {
+        let signature = payload["signature"].as_str().unwrap_or_default().to_string();
+        stub.delivered.lock().unwrap().push(signature);
+    }
+
+    async fn serve(stub: Arc<Stub>) -> String {
+        let app = Router::new()
+            .route("/", post(rpc))
+            .route("/hook", post(webhook))
+            .with_state(stub);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        url
+    }
+
+    fn stub(count: usize) -> (Stub, Pubkey) {
+        let payer = Keypair::new();
+        let address = Pubkey::new_unique();
+        let transfer = system_transaction::transfer(&payer, &address, 1_000_000, Hash::default());
+
+        let stub = Stub {
+            signatures: (0..count).map(|_| Signature::new_unique().to_string()).collect(),
+            transaction: general_purpose::STANDARD.encode(bincode::serialize(&transfer).unwrap()),
+            ..Stub::default()
+        };
+        (stub, address)
+    }
+
+    fn watch(address: Pubkey, base_url: &str, last_signature: Option<String>) -> Watch {
+        Watch {
+            id: "watch".to_string(),
+            address,
+            callback_url: format!("{}/hook", base_url),
+            secret: "secret".to_string(),
+            cluster: "localnet".to_string(),
+            active: true,
+            created_at: 0,
+            last_signature,
+            deliveries: 0,
+            failed_deliveries: 0,
+            last_error: None,
+        }
+    }
+
+    async fn wait_for_deliveries(stub: &Stub, count: usize) -> Vec<String> {
+        for _ in 0..100 {
+            if stub.delivered.lock().unwrap().len() >= count {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        // Give any unexpected extra delivery a chance to show up.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        stub.delivered.lock().unwrap().clone()
+    }
+
+    #[tokio::test]
+    async fn poll_pages_back_to_the_last_seen_signature() {
+        let (mut stub, address) = stub(SIGNATURE_PAGE_LIMIT * 2 + 30);
+        let seen = stub.signatures.split_off(SIGNATURE_PAGE_LIMIT * 2 + 10);
+        let expected: HashSet<String> = stub.signatures.iter().cloned().collect();
+        stub.signatures.extend(seen.clone());
+
+        let newest = stub.signatures[0].clone();
+        let stub = Arc::new(stub);
+        let url = serve(stub.clone()).await;
+        let cluster = Cluster::local(&url);
+
+        let registry = Arc::new(WatchRegistry::default());
+        let watch = watch(address, &url, Some(seen[0].clone()));
+        registry.insert(watch.clone()).await.unwrap();
+
+        registry.poll(&cluster, &watch).await.unwrap();
+
+        let delivered = wait_for_deliveries(&stub, expected.len()).await;
+        assert_eq!(delivered.len(), expected.len());
+        assert_eq!(delivered.into_iter().collect::<HashSet<_>>(), expected);
+        assert_eq!(registry.get("watch").await.unwrap().last_signature, Some(newest));
+    }
+
+    #[tokio::test]
+    async fn poll_failure_does_not_redeliver_earlier_transactions() {
+        let (stub, address) = stub(5);
+        // Oldest first: the third transaction fails to load the first time.
+        let oldest_first: Vec<String> = stub.signatures.iter().rev().cloned().collect();
+        stub.fail_once.lock().unwrap().insert(oldest_first[2].clone());
+
+        let stub = Arc::new(stub);
+        let url = serve(stub.clone()).await;
+        let cluster = Cluster::local(&url);
+
+        let registry = Arc::new(WatchRegistry::default());
+        registry.insert(watch(address, &url, None)).await.unwrap();
+
+        let first = registry.get("watch").await.unwrap();
+        assert!(registry.poll(&cluster, &first).await.is_err());
+        let after_failure = registry.get("watch").await.unwrap();
+        assert_eq!(after_failure.last_signature, Some(oldest_first[1].clone()));
+
+        registry.poll(&cluster, &after_failure).await.unwrap();
+
+        let mut delivered = wait_for_deliveries(&stub, oldest_first.len()).await;
+        delivered.sort();
+        let mut expected = oldest_first.clone();
+        expected.sort();
+        assert_eq!(delivered, expected);
+        assert_eq!(
+            registry.get("watch").await.unwrap().last_signature,
+            Some(oldest_first[4].clone())
+        );
+    }
+
+    #[tokio::test]
+    async fn insert_stops_at_the_watch_cap() {
+        let registry = WatchRegistry::default();
+        for i in 0..MAX_WATCHES {
+            let mut watch = watch(Pubkey::new_unique(), "http://example.com", None);
+            watch.id = i.to_string();
+            registry.insert(watch).await.unwrap();
+        }
+
+        let extra = watch(Pubkey::new_unique(), "http://example.com", None);
+        assert_eq!(
+            registry.insert(extra).await.err().unwrap(),
+            "Too many watches, delete one first"
+        );
+
+        registry.remove("0").await.unwrap();
+        let extra = watch(Pubkey::new_unique(), "http://example.com", None);
+        registry.insert(extra).await.unwrap();
+    }
+}